@@ -1,4 +1,6 @@
+mod metrics;
 mod models;
+mod server;
 mod storage;
 
 use models::*;
@@ -67,6 +69,19 @@ async fn main() -> Result<()> {
                 .help("X (Twitter) publisher ID for OAuth setup")
                 .value_name("PUBLISHER_ID"),
         )
+        .arg(
+            Arg::new("mark-seen")
+                .long("mark-seen")
+                .help("Record every current entry of the feeds as published, without publishing, and exit")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("feed")
+                .long("feed")
+                .help("Only mark the entries of this feed as seen")
+                .requires("mark-seen")
+                .value_name("FEED_ID"),
+        )
         .get_matches();
 
     let config_file = matches.get_one::<String>("config").unwrap();
@@ -76,12 +91,27 @@ async fn main() -> Result<()> {
     let linkedin_publisher_id = matches.get_one::<String>("linkedin-publisher");
     let x_oauth = matches.get_flag("x-oauth");
     let x_publisher_id = matches.get_one::<String>("x-publisher");
+    let mark_seen = matches.get_flag("mark-seen");
+    let mark_seen_feed_id = matches.get_one::<String>("feed");
 
     log::info!("Starting Populatrs RSS Publisher");
     log::info!("Config file: {}", config_file);
 
     // Load configuration
     let config = StorageManager::load_config_from_file(config_file)?;
+
+    let timezone = match config.schedule.timezone.parse::<chrono_tz::Tz>() {
+        Ok(timezone) => timezone,
+        Err(_) => {
+            log::warn!(
+                "Unknown timezone '{}' in schedule.timezone, using UTC for templates and schedules",
+                config.schedule.timezone
+            );
+            chrono_tz::Tz::UTC
+        }
+    };
+    models::set_timezone(timezone);
+
     validate_config(&config)?;
 
     // Handle LinkedIn OAuth setup command
@@ -98,12 +128,25 @@ async fn main() -> Result<()> {
         }
     }
 
+    // Start the optional HTTP listener for metrics and health checks (scheduler mode only)
+    let health = match config.http.as_ref().filter(|_| !run_once && !mark_seen) {
+        Some(http) => {
+            metrics::init();
+            let health = Arc::new(server::HealthState::new(
+                config.schedule.default_interval_minutes,
+                http.health_interval_multiplier,
+            ));
+            health.set_config_loaded();
+            let listener = server::bind(&http.listen_address).await?;
+            tokio::spawn(server::serve(listener, health.clone()));
+            Some(health)
+        }
+        None => None,
+    };
+
     // Initialize storage
-    let storage_manager = StorageManager::new(
-        config.storage.data_dir.clone(),
-        config.storage.published_posts_file.clone(),
-    );
-    storage_manager.init()?;
+    let storage_manager = StorageManager::new(&config.storage);
+    let _storage_lock = storage_manager.init()?;
 
     // Load published posts tracking
     let published_posts = Arc::new(Mutex::new(storage_manager.load_published_posts()?));
@@ -111,12 +154,39 @@ async fn main() -> Result<()> {
     // Load feed cache for ETag/conditional requests
     let feed_cache = storage_manager.load_feed_cache()?;
 
+    // Load pending retries for deliveries that failed on previous cycles
+    let retry_queue = Arc::new(Mutex::new(storage_manager.load_retry_queue()?));
+
+    // State loaded cleanly, keep a copy to restore from if a later write goes wrong
+    if let Err(e) = storage_manager.backup_state_files() {
+        log::warn!("Failed to back up state files: {}", e);
+    }
+
+    if let Some(health) = &health {
+        health.set_storage_ready();
+    }
+
+    // Handle the mark seen command
+    if mark_seen {
+        return handle_mark_seen(
+            &config,
+            mark_seen_feed_id,
+            &feed_cache,
+            &published_posts,
+            &storage_manager,
+        )
+        .await;
+    }
+
     // Initialize publishers
-    let mut publisher_manager = PublisherManager::new_with_config_path(config_file.to_string());
+    let mut publisher_manager = PublisherManager::new_with_config_path(config_file.to_string())
+        .with_data_dir(config.storage.data_dir.clone());
+    let mut publishers_ready = 0;
     for (id, publisher_config) in &config.publishers {
         if let Err(e) = publisher_manager.add_publisher(id.clone(), publisher_config) {
             log::error!("Failed to initialize publisher {}: {}", id, e);
         } else {
+            publishers_ready += 1;
             log::info!(
                 "Initialized publisher: {} ({})",
                 id,
@@ -124,6 +194,9 @@ async fn main() -> Result<()> {
             );
         }
     }
+    if let Some(health) = &health {
+        health.set_publishers_ready(publishers_ready);
+    }
     let publisher_manager = Arc::new(publisher_manager);
 
     // Initialize feeds with cache metadata
@@ -144,7 +217,9 @@ async fn main() -> Result<()> {
             feed_manager.clone(),
             publisher_manager.clone(),
             published_posts.clone(),
+            retry_queue.clone(),
             &storage_manager,
+            &config.retry,
             config.schedule.default_interval_minutes,
             dry_run,
         )
@@ -160,23 +235,29 @@ async fn main() -> Result<()> {
     let job_feed_manager = feed_manager.clone();
     let job_publisher_manager = publisher_manager.clone();
     let job_published_posts = published_posts.clone();
+    let job_retry_queue = retry_queue.clone();
     let job_storage_manager = storage_manager.clone();
+    let job_retry_config = config.retry.clone();
     let job_interval = config.schedule.default_interval_minutes;
 
     let job = Job::new_async(
-        format!("0 */{} * * * *", job_interval).as_str(), // Every N minutes
+        "0 * * * * *", // Every minute; each feed decides whether it is due
         move |_uuid, _l| {
             let feed_manager = job_feed_manager.clone();
             let publisher_manager = job_publisher_manager.clone();
             let published_posts = job_published_posts.clone();
+            let retry_queue = job_retry_queue.clone();
             let storage_manager = job_storage_manager.clone();
+            let retry_config = job_retry_config.clone();
 
             Box::pin(async move {
                 if let Err(e) = run_feed_check(
                     feed_manager,
                     publisher_manager,
                     published_posts,
+                    retry_queue,
                     &storage_manager,
+                    &retry_config,
                     job_interval,
                     dry_run,
                 )
@@ -190,39 +271,47 @@ async fn main() -> Result<()> {
 
     scheduler.add(job).await?;
 
-    // Create cleanup job (daily)
+    // Create cleanup job (daily at 2 AM by default)
     let cleanup_published_posts = published_posts.clone();
     let cleanup_storage_manager = storage_manager.clone();
+    let keep_published_days = config.schedule.keep_published_days;
+    let keep_backup_days = config.schedule.keep_backup_days;
 
-    let cleanup_job = Job::new_async(
-        "0 0 2 * * *", // Daily at 2 AM
+    let cleanup_job = Job::new_async_tz(
+        config.schedule.cleanup_cron.as_str(),
+        timezone,
         move |_uuid, _l| {
             let published_posts = cleanup_published_posts.clone();
             let storage_manager = cleanup_storage_manager.clone();
 
             Box::pin(async move {
-                log::info!("Running daily cleanup");
+                log::info!("Running cleanup");
+
+                // Back up state files before the cleanup rewrites them
+                if let Err(e) = storage_manager.backup_state_files() {
+                    log::error!("Failed to back up state files: {}", e);
+                }
 
-                // Cleanup old published posts (keep 30 days)
+                // Cleanup old published posts
                 {
                     let mut storage = published_posts.lock().await;
-                    storage.cleanup_old_posts(30);
+                    storage.cleanup_old_posts(keep_published_days);
                 }
 
                 // Save updated storage
                 {
-                    let storage = published_posts.lock().await.clone();
-                    if let Err(e) = storage_manager.save_published_posts(&storage) {
+                    let mut storage = published_posts.lock().await;
+                    if let Err(e) = storage_manager.save_published_posts(&mut storage) {
                         log::error!("Failed to save cleaned up storage: {}", e);
                     }
                 }
 
-                // Cleanup old backups (keep 7 days)
-                if let Err(e) = storage_manager.cleanup_old_backups(7) {
+                // Cleanup old backups
+                if let Err(e) = storage_manager.cleanup_old_backups(keep_backup_days) {
                     log::error!("Failed to cleanup old backups: {}", e);
                 }
 
-                log::info!("Daily cleanup completed");
+                log::info!("Cleanup completed");
             })
         },
     )?;
@@ -230,7 +319,12 @@ async fn main() -> Result<()> {
     scheduler.add(cleanup_job).await?;
 
     // Start scheduler
-    log::info!("Starting scheduler with {}min intervals", job_interval);
+    log::info!(
+        "Starting scheduler: feeds checked every {}min unless set otherwise, cleanup at '{}' ({})",
+        job_interval,
+        config.schedule.cleanup_cron,
+        timezone
+    );
     scheduler.start().await?;
 
     // Run initial check
@@ -239,7 +333,9 @@ async fn main() -> Result<()> {
         feed_manager.clone(),
         publisher_manager.clone(),
         published_posts.clone(),
+        retry_queue.clone(),
         &storage_manager,
+        &config.retry,
         config.schedule.default_interval_minutes,
         dry_run,
     )
@@ -256,16 +352,79 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+#[expect(clippy::too_many_arguments)]
 async fn run_feed_check(
     feed_manager: Arc<Mutex<FeedManager>>,
     publisher_manager: Arc<PublisherManager>,
     published_posts: Arc<Mutex<PublishedPostsStorage>>,
+    retry_queue: Arc<Mutex<RetryQueue>>,
     storage_manager: &StorageManager,
+    retry_config: &RetryConfig,
     default_interval_minutes: u64,
     dry_run: bool,
 ) -> Result<()> {
+    // Most scheduler ticks have nothing to do
+    let feeds_due = feed_manager
+        .lock()
+        .await
+        .has_due_feeds(default_interval_minutes);
+    let retries_due = !dry_run && retry_queue.lock().await.has_due(chrono::Utc::now());
+    if !feeds_due && !retries_due {
+        log::debug!("No feeds or retries due");
+        metrics::record_idle_tick();
+        return Ok(());
+    }
+
     log::info!("Starting feed check cycle");
 
+    // Retry deliveries that failed on previous cycles before looking for new posts
+    if !dry_run {
+        process_retry_queue(
+            &publisher_manager,
+            &published_posts,
+            &retry_queue,
+            retry_config,
+        )
+        .await;
+    }
+
+    // A cycle only counts as successful if something worked
+    let mut feeds_fetched = 0;
+    let mut feeds_failed = 0;
+
+    // New feeds set to mark_all_seen record their current entries instead of publishing them
+    let first_run_results = {
+        let mut manager = feed_manager.lock().await;
+        let feed_ids = manager.first_run_to_mark_seen(default_interval_minutes);
+        manager.fetch_all_posts(&feed_ids).await
+    };
+
+    for (feed_id, result) in first_run_results {
+        match result {
+            Ok(posts) if dry_run => {
+                feeds_fetched += 1;
+                log::info!(
+                    "[DRY RUN] Would mark {} entries of new feed {} as published",
+                    posts.len(),
+                    feed_id
+                );
+            }
+            Ok(posts) => {
+                feeds_fetched += 1;
+                let marked = mark_posts_seen(&published_posts, &posts).await;
+                log::info!(
+                    "First check of feed {}: marked {} entries as published without publishing them",
+                    feed_id,
+                    marked
+                );
+            }
+            Err(e) => {
+                feeds_failed += 1;
+                log::error!("Failed to fetch feed {}: {}", feed_id, e);
+            }
+        }
+    }
+
     // Check all feeds for new posts
     let feed_results = {
         let mut manager = feed_manager.lock().await;
@@ -274,10 +433,12 @@ async fn run_feed_check(
 
     let mut total_new_posts = 0;
     let mut total_published = 0;
+    let mut total_attempted = 0;
 
     for (feed_id, result) in feed_results {
         match result {
             Ok(new_posts) => {
+                feeds_fetched += 1;
                 if new_posts.is_empty() {
                     log::debug!("No new posts in feed: {}", feed_id);
                     continue;
@@ -326,6 +487,7 @@ async fn run_feed_check(
                     }
 
                     // Publish to all configured publishers
+                    total_attempted += 1;
                     let results = publisher_manager
                         .publish_to_all(&post, &publisher_ids)
                         .await;
@@ -343,12 +505,25 @@ async fn run_feed_check(
                             }
                             Err(e) => {
                                 log::error!("✗ Failed to publish to {}: {}", publisher_id, e);
+                                let queued = retry_queue.lock().await.enqueue(
+                                    &post,
+                                    publisher_id,
+                                    e.to_string(),
+                                    retry_config,
+                                );
+                                if queued {
+                                    log::info!(
+                                        "Queued \"{}\" for retry on {}",
+                                        post.title,
+                                        publisher_id
+                                    );
+                                }
                                 publish_results.push((publisher_id.clone(), false, e.to_string()));
                             }
                         }
                     }
 
-                    // Mark as published (even if some publishers failed)
+                    // Mark as published; failed publishers are retried from the retry queue
                     {
                         let mut storage = published_posts.lock().await;
                         storage.mark_published(&post, publish_results);
@@ -369,6 +544,7 @@ async fn run_feed_check(
                 }
             }
             Err(e) => {
+                feeds_failed += 1;
                 log::error!("Failed to fetch feed {}: {}", feed_id, e);
             }
         }
@@ -376,14 +552,25 @@ async fn run_feed_check(
 
     // Save updated published posts storage
     if !dry_run {
-        let storage = published_posts.lock().await.clone();
-        if let Err(e) = storage_manager.save_published_posts(&storage) {
-            log::error!("Failed to save published posts: {}", e);
+        {
+            let mut storage = published_posts.lock().await;
+            if let Err(e) = storage_manager.save_published_posts(&mut storage) {
+                log::error!("Failed to save published posts: {}", e);
+            }
+        }
+
+        let queue = retry_queue.lock().await.clone();
+        if !queue.is_empty() {
+            log::info!("{} failed deliveries pending retry", queue.len());
+        }
+        if let Err(e) = storage_manager.save_retry_queue(&queue) {
+            log::error!("Failed to save retry queue: {}", e);
         }
     }
 
-    // Save updated feed cache (ETags, Last-Modified, etc.)
-    {
+    // Save updated feed cache (ETags, Last-Modified, last post date, etc.).
+    // A dry run must not mark anything as seen.
+    if !dry_run {
         let manager = feed_manager.lock().await;
         let cache = manager.get_cache_metadata();
         if let Err(e) = storage_manager.save_feed_cache(&cache) {
@@ -391,15 +578,168 @@ async fn run_feed_check(
         }
     }
 
-    log::info!(
-        "Feed check cycle completed: {} new posts found, {} published",
-        total_new_posts,
-        total_published
-    );
+    let all_fetches_failed = feeds_failed > 0 && feeds_fetched == 0;
+    let all_publishes_failed = total_attempted > 0 && total_published == 0;
+    if all_fetches_failed || all_publishes_failed {
+        metrics::record_cycle_failure();
+        log::warn!(
+            "Feed check cycle failed: {}/{} feeds fetched, {}/{} posts published",
+            feeds_fetched,
+            feeds_fetched + feeds_failed,
+            total_published,
+            total_attempted
+        );
+    } else {
+        metrics::record_cycle_success();
+        log::info!(
+            "Feed check cycle completed: {} new posts found, {} published",
+            total_new_posts,
+            total_published
+        );
+    }
 
     Ok(())
 }
 
+/// Record posts as published without publishing them; returns how many were new
+async fn mark_posts_seen(published_posts: &Mutex<PublishedPostsStorage>, posts: &[Post]) -> usize {
+    let mut storage = published_posts.lock().await;
+    let mut marked = 0;
+    for post in posts {
+        if !storage.is_published(post) {
+            storage.mark_published(post, Vec::new());
+            marked += 1;
+        }
+    }
+    marked
+}
+
+async fn handle_mark_seen(
+    config: &AppConfig,
+    feed_id: Option<&String>,
+    feed_cache: &storage::FeedCacheStorage,
+    published_posts: &Mutex<PublishedPostsStorage>,
+    storage_manager: &StorageManager,
+) -> Result<()> {
+    let feed_ids = match feed_id {
+        Some(feed_id) => {
+            if !config.feeds.iter().any(|feed| &feed.id == feed_id) {
+                return Err(anyhow::anyhow!("Feed '{}' not found in config", feed_id));
+            }
+            vec![feed_id.clone()]
+        }
+        None => config.feeds.iter().map(|feed| feed.id.clone()).collect(),
+    };
+
+    let mut feed_manager = FeedManager::new();
+    feed_manager.load_feeds_with_cache(config.feeds.clone(), config.youtube.clone(), feed_cache);
+
+    let mut failed = 0;
+    for (feed_id, result) in feed_manager.fetch_all_posts(&feed_ids).await {
+        match result {
+            Ok(posts) => {
+                let marked = mark_posts_seen(published_posts, &posts).await;
+                println!(
+                    "✓ {}: {} entries, {} newly marked as published",
+                    feed_id,
+                    posts.len(),
+                    marked
+                );
+            }
+            Err(e) => {
+                log::error!("Failed to fetch feed {}: {}", feed_id, e);
+                failed += 1;
+            }
+        }
+    }
+
+    storage_manager.save_published_posts(&mut *published_posts.lock().await)?;
+    storage_manager.save_feed_cache(&feed_manager.get_cache_metadata())?;
+
+    if failed > 0 {
+        return Err(anyhow::anyhow!("Failed to fetch {} feeds", failed));
+    }
+    Ok(())
+}
+
+/// Retry every queued delivery that is due, only on the publishers that failed
+async fn process_retry_queue(
+    publisher_manager: &PublisherManager,
+    published_posts: &Mutex<PublishedPostsStorage>,
+    retry_queue: &Mutex<RetryQueue>,
+    retry_config: &RetryConfig,
+) {
+    let due = retry_queue.lock().await.take_due(chrono::Utc::now());
+    if due.is_empty() {
+        return;
+    }
+
+    log::info!("Retrying {} failed deliveries", due.len());
+
+    for entry in due {
+        if !publisher_manager.has_publisher(&entry.publisher_id) {
+            log::warn!(
+                "Dropping retry of \"{}\": publisher {} is no longer configured",
+                entry.post.title,
+                entry.publisher_id
+            );
+            continue;
+        }
+
+        let post = entry.post.clone();
+        let publisher_id = entry.publisher_id.clone();
+        let attempt = entry.attempts + 1;
+
+        match publisher_manager.publish_to(&post, &publisher_id).await {
+            Ok(message) => {
+                log::info!(
+                    "✓ Published to {} on attempt {}: {}",
+                    publisher_id,
+                    attempt,
+                    message
+                );
+                published_posts
+                    .lock()
+                    .await
+                    .update_result(&post, &publisher_id, true, message);
+            }
+            Err(e) => {
+                let rescheduled =
+                    retry_queue
+                        .lock()
+                        .await
+                        .reschedule(entry, e.to_string(), retry_config);
+                if rescheduled {
+                    log::warn!(
+                        "✗ Retry {} of \"{}\" on {} failed: {}",
+                        attempt,
+                        post.title,
+                        publisher_id,
+                        e
+                    );
+                } else {
+                    log::error!(
+                        "✗ Giving up on \"{}\" for {} after {} attempts: {}",
+                        post.title,
+                        publisher_id,
+                        attempt,
+                        e
+                    );
+                    published_posts.lock().await.update_result(
+                        &post,
+                        &publisher_id,
+                        false,
+                        format!("Gave up after {} attempts: {}", attempt, e),
+                    );
+                }
+            }
+        }
+
+        // Small delay between posts to avoid rate limiting
+        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+    }
+}
+
 fn validate_config(config: &AppConfig) -> Result<()> {
     if config.feeds.is_empty() {
         return Err(anyhow::anyhow!("No feeds configured"));
@@ -409,8 +749,15 @@ fn validate_config(config: &AppConfig) -> Result<()> {
         return Err(anyhow::anyhow!("No publishers configured"));
     }
 
+    parse_cron(&config.schedule.cleanup_cron)
+        .map_err(|e| anyhow::anyhow!("schedule.cleanup_cron: {}", e))?;
+
     // Check that all feed publisher references exist
     for feed in &config.feeds {
+        if let Some(expression) = &feed.cron {
+            parse_cron(expression).map_err(|e| anyhow::anyhow!("Feed '{}': {}", feed.id, e))?;
+        }
+
         for publisher_id in &feed.publishers {
             if !config.publishers.contains_key(publisher_id) {
                 return Err(anyhow::anyhow!(
@@ -437,6 +784,11 @@ fn get_publisher_type_name(config: &PublisherConfig) -> &'static str {
         PublisherConfig::Bluesky { .. } => "Bluesky",
         PublisherConfig::Threads { .. } => "Threads",
         PublisherConfig::Discord { .. } => "Discord",
+        PublisherConfig::Slack { .. } => "Slack",
+        PublisherConfig::Email { .. } => "Email",
+        PublisherConfig::Ntfy { .. } => "ntfy",
+        PublisherConfig::Gotify { .. } => "Gotify",
+        PublisherConfig::Webhook { .. } => "Webhook",
     }
 }
 
@@ -461,6 +813,7 @@ async fn handle_linkedin_oauth_setup(
             publisher_id.to_string(),
             publisher_config,
             Some(config_file.to_string()),
+            Some(config.storage.data_dir.clone()),
         )?;
 
         // Downcast to LinkedInPublisher to access OAuth methods
@@ -506,6 +859,7 @@ async fn handle_x_oauth_setup(
             publisher_id.to_string(),
             publisher_config,
             Some(config_file.to_string()),
+            Some(config.storage.data_dir.clone()),
         )?;
 
         // Downcast to XPublisher to access OAuth methods
@@ -0,0 +1,173 @@
+use prometheus::{
+    register_gauge, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    Encoder, Gauge, HistogramVec, IntCounter, IntCounterVec, TextEncoder,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+
+pub static FEED_FETCHES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "populatrs_feed_fetches_total",
+        "Feed fetches by feed and result (success or failure)",
+        &["feed_id", "result"]
+    )
+    .unwrap()
+});
+
+pub static FEED_FETCH_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "populatrs_feed_fetch_duration_seconds",
+        "Time spent fetching a feed, including retries",
+        &["feed_id"]
+    )
+    .unwrap()
+});
+
+pub static FEED_NOT_MODIFIED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "populatrs_feed_not_modified_total",
+        "Feed fetches answered with 304 Not Modified",
+        &["feed_id"]
+    )
+    .unwrap()
+});
+
+pub static FEED_UNCHANGED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "populatrs_feed_unchanged_total",
+        "Feed fetches skipped because the content hash did not change",
+        &["feed_id"]
+    )
+    .unwrap()
+});
+
+pub static PUBLISHES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "populatrs_publish_total",
+        "Publish attempts by publisher and result (success or failure)",
+        &["publisher_type", "publisher_id", "result"]
+    )
+    .unwrap()
+});
+
+pub static CYCLES: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "populatrs_feed_check_cycles_total",
+        "Feed check cycles that completed successfully"
+    )
+    .unwrap()
+});
+
+pub static FAILED_CYCLES: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "populatrs_feed_check_cycles_failed_total",
+        "Feed check cycles in which every feed fetch or every publish failed"
+    )
+    .unwrap()
+});
+
+pub static LAST_SUCCESSFUL_CYCLE: LazyLock<Gauge> = LazyLock::new(|| {
+    register_gauge!(
+        "populatrs_last_successful_cycle_timestamp_seconds",
+        "Unix time of the last feed check cycle that completed, including ticks with nothing due"
+    )
+    .unwrap()
+});
+
+pub static SECONDS_SINCE_LAST_SUCCESSFUL_CYCLE: LazyLock<Gauge> = LazyLock::new(|| {
+    register_gauge!(
+        "populatrs_seconds_since_last_successful_cycle",
+        "Seconds since the last feed check cycle that completed (-1 if none yet)"
+    )
+    .unwrap()
+});
+
+/// Whether the last feed check cycle that ran failed
+static LAST_CYCLE_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Register every metric up front so they are exported before their first update
+pub fn init() {
+    LazyLock::force(&FEED_FETCHES);
+    LazyLock::force(&FEED_FETCH_DURATION);
+    LazyLock::force(&FEED_NOT_MODIFIED);
+    LazyLock::force(&FEED_UNCHANGED);
+    LazyLock::force(&PUBLISHES);
+    LazyLock::force(&CYCLES);
+    LazyLock::force(&FAILED_CYCLES);
+    LazyLock::force(&LAST_SUCCESSFUL_CYCLE);
+    LazyLock::force(&SECONDS_SINCE_LAST_SUCCESSFUL_CYCLE);
+}
+
+pub fn record_cycle_success() {
+    CYCLES.inc();
+    LAST_CYCLE_FAILED.store(false, Ordering::Relaxed);
+    LAST_SUCCESSFUL_CYCLE.set(chrono::Utc::now().timestamp() as f64);
+}
+
+/// A failed cycle leaves the last successful cycle time, and so the health
+/// check, untouched
+pub fn record_cycle_failure() {
+    FAILED_CYCLES.inc();
+    LAST_CYCLE_FAILED.store(true, Ordering::Relaxed);
+}
+
+/// A scheduler tick with no feed or retry due still shows the scheduler is
+/// alive, so feeds on sparse schedules stay healthy. After a failed cycle
+/// only a successful one counts again.
+pub fn record_idle_tick() {
+    if !LAST_CYCLE_FAILED.load(Ordering::Relaxed) {
+        LAST_SUCCESSFUL_CYCLE.set(chrono::Utc::now().timestamp() as f64);
+    }
+}
+
+/// Unix time of the last successful cycle, if any cycle has completed
+pub fn last_successful_cycle() -> Option<i64> {
+    let timestamp = LAST_SUCCESSFUL_CYCLE.get();
+    (timestamp > 0.0).then_some(timestamp as i64)
+}
+
+pub fn result_label(success: bool) -> &'static str {
+    if success {
+        "success"
+    } else {
+        "failure"
+    }
+}
+
+/// Render all metrics in the Prometheus text exposition format
+pub fn render() -> String {
+    let since_last_cycle = match last_successful_cycle() {
+        Some(timestamp) => (chrono::Utc::now().timestamp() - timestamp) as f64,
+        None => -1.0,
+    };
+    SECONDS_SINCE_LAST_SUCCESSFUL_CYCLE.set(since_last_cycle);
+
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+        log::error!("Failed to encode metrics: {}", e);
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_tick_after_failure() {
+        LAST_SUCCESSFUL_CYCLE.set(1.0);
+        record_idle_tick();
+        assert!(last_successful_cycle().unwrap() > 1);
+
+        // A failed cycle is not hidden by the idle ticks that follow it
+        LAST_SUCCESSFUL_CYCLE.set(1.0);
+        record_cycle_failure();
+        record_idle_tick();
+        assert_eq!(last_successful_cycle(), Some(1));
+
+        record_cycle_success();
+        LAST_SUCCESSFUL_CYCLE.set(1.0);
+        record_idle_tick();
+        assert!(last_successful_cycle().unwrap() > 1);
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -8,6 +9,9 @@ pub struct AppConfig {
     pub youtube: Option<YouTubeGlobalConfig>,
     pub schedule: ScheduleConfig,
     pub storage: StorageConfig,
+    #[serde(default)]
+    pub retry: RetryConfig,
+    pub http: Option<HttpConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,8 +30,83 @@ pub struct FeedConfig {
     pub enabled: bool,
     pub publishers: Vec<String>, // Publisher IDs to publish this feed to
     pub check_interval_minutes: Option<u64>,
+    /// When to check, as a cron expression with seconds in `schedule.timezone`;
+    /// takes precedence over `check_interval_minutes`
+    pub cron: Option<String>,
     pub max_retries: Option<u32>,
     pub retry_delay_seconds: Option<u64>,
+    /// Hashtag overrides by category, e.g. "rust-lang" -> "#RustLang" (empty drops the tag)
+    #[serde(default)]
+    pub hashtag_map: HashMap<String, String>,
+    /// Hashtags added to every post of this feed
+    #[serde(default)]
+    pub extra_hashtags: Vec<String>,
+    /// Posts published per check (default: 2, the newest)
+    pub max_posts_per_check: Option<usize>,
+    /// Publish every post missed since the last check, oldest first
+    #[serde(default)]
+    pub publish_backlog: bool,
+    /// Skip posts published more than this many hours ago
+    pub max_post_age_hours: Option<u64>,
+    /// What the first check of a new feed publishes
+    #[serde(default)]
+    pub on_first_run: FirstRun,
+}
+
+/// What the first check of a feed publishes, before any post has been seen
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub enum FirstRun {
+    /// The newest posts, as on any other check
+    #[default]
+    PublishLatest,
+    /// Nothing: every current entry is recorded as published
+    MarkAllSeen,
+    /// Every post published since the date, oldest first
+    PublishSince(DateTime<Utc>),
+}
+
+impl TryFrom<String> for FirstRun {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "publish_latest" => Ok(Self::PublishLatest),
+            "mark_all_seen" => Ok(Self::MarkAllSeen),
+            _ => {
+                let date = value.strip_prefix("publish_since:").ok_or_else(|| {
+                    format!(
+                        "invalid on_first_run '{}', expected publish_latest, mark_all_seen or publish_since:<date>",
+                        value
+                    )
+                })?;
+                // RFC 3339, or a plain date meaning midnight UTC
+                DateTime::parse_from_rfc3339(date)
+                    .map(|date| date.with_timezone(&Utc))
+                    .or_else(|_| {
+                        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                            .map(|date| date.and_hms_opt(0, 0, 0).unwrap().and_utc())
+                    })
+                    .map(Self::PublishSince)
+                    .map_err(|_| {
+                        format!(
+                            "invalid date '{}' in on_first_run, expected YYYY-MM-DD or RFC 3339",
+                            date
+                        )
+                    })
+            }
+        }
+    }
+}
+
+impl From<FirstRun> for String {
+    fn from(value: FirstRun) -> Self {
+        match value {
+            FirstRun::PublishLatest => "publish_latest".to_string(),
+            FirstRun::MarkAllSeen => "mark_all_seen".to_string(),
+            FirstRun::PublishSince(date) => format!("publish_since:{}", date.to_rfc3339()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +132,11 @@ pub enum PublisherConfig {
         parse_mode: Option<String>,
         message_thread_id: Option<String>,
         template: Option<String>,
+        /// Send the post's image with the text as caption (default: true)
+        attach_image: Option<bool>,
+        /// Inline keyboard buttons below the message
+        #[serde(default)]
+        buttons: Vec<TelegramButton>,
     },
     X {
         client_id: String,
@@ -66,6 +150,10 @@ pub enum PublisherConfig {
         server_url: String,
         access_token: String,
         template: Option<String>,
+        /// Attach the post's lead image (default: true)
+        attach_image: Option<bool>,
+        /// Image description; defaults to the post title
+        alt_text_template: Option<String>,
     },
     LinkedIn {
         client_id: String,
@@ -94,6 +182,8 @@ pub enum PublisherConfig {
         password: String,
         pds_url: Option<String>,
         template: Option<String>,
+        /// Attach a link card built from the page's OpenGraph data (default: true)
+        link_card: Option<bool>,
     },
     Threads {
         access_token: String,
@@ -102,20 +192,186 @@ pub enum PublisherConfig {
     },
     Discord {
         webhook_url: String,
+        /// Message content; empty by default when `embed` is on
+        template: Option<String>,
+        /// Send the post as a rich embed (default: false)
+        embed: Option<bool>,
+        embed_description_template: Option<String>,
+        #[serde(default)]
+        embed_image: DiscordImage,
+        /// Embed side color as "#RRGGBB"
+        color: Option<String>,
+        username: Option<String>,
+        avatar_url: Option<String>,
+        /// Post into an existing thread
+        thread_id: Option<String>,
+        /// Create a forum post with this title (a template)
+        thread_name: Option<String>,
+    },
+    Slack {
+        /// Incoming webhook URL; alternatively use bot_token and channel
+        webhook_url: Option<String>,
+        bot_token: Option<String>,
+        channel: Option<String>,
+        /// Reply in this thread (chat.postMessage only)
+        thread_ts: Option<String>,
+        template: Option<String>,
+    },
+    Email {
+        smtp_host: String,
+        /// Defaults to 587 for Starttls, 465 for Tls and 25 for Plain
+        smtp_port: Option<u16>,
+        #[serde(default)]
+        security: SmtpSecurity,
+        username: Option<String>,
+        password: Option<String>,
+        from: String,
+        to: Vec<String>,
+        subject_template: Option<String>,
+        /// HTML body
+        template: Option<String>,
+        /// Plain-text alternative (default: the HTML body without tags)
+        text_template: Option<String>,
+    },
+    Ntfy {
+        /// Full topic URL, e.g. https://ntfy.sh/my-topic
+        topic_url: String,
+        /// Bearer token; alternatively use username and password
+        access_token: Option<String>,
+        username: Option<String>,
+        password: Option<String>,
+        title_template: Option<String>,
+        tags: Option<Vec<String>>,
+        /// 1 (min) to 5 (max)
+        priority: Option<u8>,
+        template: Option<String>,
+    },
+    Gotify {
+        server_url: String,
+        app_token: String,
+        /// 0 to 10
+        priority: Option<u8>,
+        /// Render the message as markdown in Gotify clients (default: true)
+        markdown: Option<bool>,
+        title_template: Option<String>,
+        template: Option<String>,
+    },
+    Webhook {
+        url: String,
+        method: Option<String>,
+        /// Values may reference environment variables as `${VAR}`
+        headers: Option<HashMap<String, String>>,
         template: Option<String>,
+        /// Status codes that count as success (default: any 2xx)
+        success_status_codes: Option<Vec<u16>>,
     },
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramButton {
+    /// Button label (a template)
+    pub text: String,
+    /// Link target (a template, default: the post URL)
+    pub url: Option<String>,
+}
+
+/// Where a Discord embed shows the post's image
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub enum DiscordImage {
+    #[default]
+    Thumbnail,
+    Image,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    #[default]
+    Starttls,
+    Tls,
+    Plain,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduleConfig {
     pub default_interval_minutes: u64,
+    /// IANA name used by templates and cron expressions
     pub timezone: String,
+    /// When old published posts and backups are removed (cron with seconds)
+    #[serde(default = "default_cleanup_cron")]
+    pub cleanup_cron: String,
+    /// Days a published post is remembered
+    #[serde(default = "default_keep_published_days")]
+    pub keep_published_days: u64,
+    /// Days state file backups are kept
+    #[serde(default = "default_keep_backup_days")]
+    pub keep_backup_days: u64,
+}
+
+fn default_cleanup_cron() -> String {
+    "0 0 2 * * *".to_string()
+}
+
+fn default_keep_published_days() -> u64 {
+    30
+}
+
+fn default_keep_backup_days() -> u64 {
+    7
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StorageConfig {
     pub data_dir: String,
     pub published_posts_file: String,
+    #[serde(default)]
+    pub backend: StorageBackend,
+    /// SQLite database file inside `data_dir` (only used by the Sqlite backend)
+    #[serde(default = "default_database_file")]
+    pub database_file: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub enum StorageBackend {
+    #[default]
+    Json,
+    Sqlite,
+}
+
+fn default_database_file() -> String {
+    "populatrs.db".to_string()
+}
+
+/// Optional HTTP listener serving `/metrics`, `/healthz` and `/readyz`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpConfig {
+    pub listen_address: String,
+    /// `/healthz` fails when no cycle finished within this many default intervals
+    #[serde(default = "default_health_interval_multiplier")]
+    pub health_interval_multiplier: u64,
+}
+
+fn default_health_interval_multiplier() -> u64 {
+    3
+}
+
+/// Retry policy for deliveries that failed on some publishers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Total delivery attempts per publisher, including the first one
+    pub max_attempts: u32,
+    pub base_delay_minutes: u64,
+    pub max_delay_minutes: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay_minutes: 5,
+            max_delay_minutes: 360,
+        }
+    }
 }
 
 impl Default for AppConfig {
@@ -127,11 +383,88 @@ impl Default for AppConfig {
             schedule: ScheduleConfig {
                 default_interval_minutes: 60,
                 timezone: "UTC".to_string(),
+                cleanup_cron: default_cleanup_cron(),
+                keep_published_days: default_keep_published_days(),
+                keep_backup_days: default_keep_backup_days(),
             },
             storage: StorageConfig {
                 data_dir: "./data".to_string(),
                 published_posts_file: "published_posts.json".to_string(),
+                backend: StorageBackend::default(),
+                database_file: default_database_file(),
             },
+            retry: RetryConfig::default(),
+            http: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_run_from_string() {
+        let parse = |value: &str| FirstRun::try_from(value.to_string());
+
+        assert_eq!(parse("publish_latest"), Ok(FirstRun::PublishLatest));
+        assert_eq!(parse("mark_all_seen"), Ok(FirstRun::MarkAllSeen));
+        // A plain date is midnight UTC
+        assert_eq!(
+            parse("publish_since:2024-10-21"),
+            Ok(FirstRun::PublishSince(
+                "2024-10-21T00:00:00Z".parse().unwrap()
+            ))
+        );
+        assert_eq!(
+            parse("publish_since:2024-10-21T08:00:00+02:00"),
+            Ok(FirstRun::PublishSince(
+                "2024-10-21T06:00:00Z".parse().unwrap()
+            ))
+        );
+
+        for invalid in [
+            "",
+            "publish_all",
+            "publish_since:",
+            "publish_since:21/10/2024",
+            "publish_since 2024-10-21",
+        ] {
+            assert!(parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_first_run_round_trip() {
+        for first_run in [
+            FirstRun::PublishLatest,
+            FirstRun::MarkAllSeen,
+            FirstRun::PublishSince("2024-10-21T06:30:00Z".parse().unwrap()),
+        ] {
+            let json = serde_json::to_string(&first_run).unwrap();
+            assert_eq!(serde_json::from_str::<FirstRun>(&json).unwrap(), first_run);
+            assert_eq!(FirstRun::try_from(String::from(first_run)), Ok(first_run));
+        }
+        assert_eq!(
+            serde_json::to_string(&FirstRun::MarkAllSeen).unwrap(),
+            "\"mark_all_seen\""
+        );
+    }
+
+    #[test]
+    fn test_schedule_defaults() {
+        let schedule: ScheduleConfig = serde_json::from_str(
+            r#"{ "default_interval_minutes": 30, "timezone": "Europe/Madrid" }"#,
+        )
+        .unwrap();
+        assert_eq!(schedule.cleanup_cron, "0 0 2 * * *");
+        assert_eq!(schedule.keep_published_days, 30);
+        assert_eq!(schedule.keep_backup_days, 7);
+        assert!(crate::models::parse_cron(&schedule.cleanup_cron).is_ok());
+
+        let default = AppConfig::default().schedule;
+        assert_eq!(default.cleanup_cron, schedule.cleanup_cron);
+        assert_eq!(default.keep_published_days, schedule.keep_published_days);
+        assert_eq!(default.keep_backup_days, schedule.keep_backup_days);
+    }
+}
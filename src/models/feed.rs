@@ -1,16 +1,29 @@
+use crate::metrics;
 use crate::models::{
-    FeedConfig, FeedTypeConfig, Post, YouTubeClient, YouTubeConfig, YouTubeGlobalConfig,
+    build_hashtags, next_run, parse_cron, timezone, FeedConfig, FeedTypeConfig, FirstRun, Post,
+    YouTubeClient, YouTubeConfig, YouTubeGlobalConfig,
 };
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use croner::Cron;
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 
+/// Posts published per check unless `max_posts_per_check` says otherwise
+const DEFAULT_MAX_POSTS_PER_CHECK: usize = 2;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct FeedCacheMetadata {
     pub etag: Option<String>,
     pub last_modified: Option<String>,
     pub last_content_hash: Option<String>,
+    /// `Feed::last_checked` as of the last save
+    pub last_checked: Option<DateTime<Utc>>,
+    /// `Feed::last_post_date` as of the last save
+    pub last_post_date: Option<DateTime<Utc>>,
+    /// `Feed::seeded` as of the last save
+    #[serde(default)]
+    pub seeded: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -18,21 +31,31 @@ pub struct Feed {
     pub config: FeedConfig,
     pub last_checked: Option<DateTime<Utc>>,
     pub last_post_date: Option<DateTime<Utc>>,
+    /// Every entry was fetched once by `fetch_all_posts`, even if there
+    /// were none
+    pub seeded: bool,
     pub cache_metadata: FeedCacheMetadata,
     pub youtube_config: Option<YouTubeGlobalConfig>,
     client: Client,
+    /// Parsed `cron` expression
+    schedule: Option<Cron>,
+    /// Set while `fetch_all_posts` runs
+    select_all: bool,
 }
 
 impl Feed {
     #[allow(dead_code)]
     pub fn new(config: FeedConfig, youtube_config: Option<YouTubeGlobalConfig>) -> Self {
         Self {
+            schedule: parse_schedule(&config),
             config,
             last_checked: None,
             last_post_date: None,
+            seeded: false,
             cache_metadata: FeedCacheMetadata::default(),
             youtube_config,
             client: Client::new(),
+            select_all: false,
         }
     }
 
@@ -42,12 +65,15 @@ impl Feed {
         youtube_config: Option<YouTubeGlobalConfig>,
     ) -> Self {
         Self {
+            schedule: parse_schedule(&config),
             config,
-            last_checked: None,
-            last_post_date: None,
+            last_checked: cache_metadata.last_checked,
+            last_post_date: cache_metadata.last_post_date,
+            seeded: cache_metadata.seeded,
             cache_metadata,
             youtube_config,
             client: Client::new(),
+            select_all: false,
         }
     }
 
@@ -56,8 +82,23 @@ impl Feed {
         let base_delay = self.config.retry_delay_seconds.unwrap_or(2);
 
         for attempt in 0..=max_retries {
-            match self.fetch_posts_attempt().await {
-                Ok(posts) => {
+            let result = self.fetch_posts_attempt().await;
+            // Every attempt counts as a check, so a feed that fails or has no
+            // validators (YouTube) still waits for its next cron or interval slot
+            self.last_checked = Some(Utc::now());
+
+            match result {
+                Ok(mut posts) => {
+                    for post in &mut posts {
+                        post.feed_name = self.config.name.clone();
+                        post.feed_url = self.source_url();
+                        post.hashtags = build_hashtags(
+                            &post.categories,
+                            &self.config.hashtag_map,
+                            &self.config.extra_hashtags,
+                        );
+                    }
+
                     if attempt > 0 {
                         log::info!(
                             "Successfully fetched feed {} on attempt {}/{}",
@@ -99,6 +140,46 @@ impl Feed {
         unreachable!()
     }
 
+    /// Fetch every current entry of the feed, ignoring the cache validators
+    /// and without selecting any for publishing. The feed's last post date
+    /// moves past them, so later checks only see newer posts.
+    pub async fn fetch_all_posts(&mut self) -> Result<Vec<Post>> {
+        self.cache_metadata = FeedCacheMetadata::default();
+        self.select_all = true;
+        let result = self.fetch_posts().await;
+        self.select_all = false;
+        result
+    }
+
+    /// No post of this feed has been seen yet, and its entries were never
+    /// all fetched. A feed that was empty when seeded is past its first run.
+    pub fn is_first_run(&self) -> bool {
+        self.last_post_date.is_none() && !self.seeded
+    }
+
+    /// Public URL of the feed source, exposed to templates as `feed_url`
+    pub fn source_url(&self) -> String {
+        match &self.config.config {
+            FeedTypeConfig::Rss { url } => url.clone(),
+            FeedTypeConfig::Youtube {
+                channel_id,
+                playlist_id,
+                username,
+                ..
+            } => {
+                if let Some(playlist_id) = playlist_id {
+                    format!("https://www.youtube.com/playlist?list={}", playlist_id)
+                } else if let Some(channel_id) = channel_id {
+                    format!("https://www.youtube.com/channel/{}", channel_id)
+                } else if let Some(username) = username {
+                    format!("https://www.youtube.com/user/{}", username)
+                } else {
+                    String::new()
+                }
+            }
+        }
+    }
+
     async fn fetch_posts_attempt(&mut self) -> Result<Vec<Post>> {
         log::info!("Fetching posts from feed: {}", self.config.name);
 
@@ -176,7 +257,9 @@ impl Feed {
                 "Feed {} not modified (304), skipping download",
                 self.config.name
             );
-            self.last_checked = Some(Utc::now());
+            metrics::FEED_NOT_MODIFIED
+                .with_label_values(&[&self.config.id])
+                .inc();
             return Ok(Vec::new()); // Return empty vec, content hasn't changed
         }
 
@@ -219,13 +302,14 @@ impl Feed {
                     "Feed {} content unchanged (same hash), skipping parse",
                     self.config.name
                 );
-                self.last_checked = Some(Utc::now());
+                metrics::FEED_UNCHANGED
+                    .with_label_values(&[&self.config.id])
+                    .inc();
                 return Ok(Vec::new());
             }
         }
 
         self.cache_metadata.last_content_hash = Some(content_hash);
-        self.last_checked = Some(Utc::now());
 
         // Try to parse as RSS first
         if let Ok(channel) = content.parse::<rss::Channel>() {
@@ -243,165 +327,170 @@ impl Feed {
     fn process_youtube_posts(&mut self, mut posts: Vec<Post>) -> Result<Vec<Post>> {
         log::info!("Processing {} YouTube videos", posts.len());
 
-        if posts.is_empty() {
-            return Ok(posts);
-        }
-
-        // Sort by publication date (newest first) to get the latest ones
-        posts.sort_by_key(|b| std::cmp::Reverse(b.published));
-
-        // Update our last post date to the most recent post
-        if let Some(latest_post) = posts.first() {
-            self.last_post_date = Some(latest_post.published);
+        // Update feed_id to use the correct feed ID instead of hardcoded "youtube"
+        for post in &mut posts {
+            post.feed_id = self.config.id.clone();
         }
 
         // For YouTube, we want the latest posts regardless of previous processing
         // No date filtering - just take the most recent ones
-        log::info!("Available videos after sorting:");
-        for (i, post) in posts.iter().enumerate() {
-            log::info!("  {}: '{}' ({})", i + 1, post.title, post.published);
-        }
-
-        let mut filtered_posts: Vec<Post> = posts
-            .into_iter()
-            .take(2) // Limit to 2 most recent
-            .collect();
-
-        log::info!(
-            "Selected {} videos after taking 2 most recent",
-            filtered_posts.len()
-        );
-
-        // Update feed_id to use the correct feed ID instead of hardcoded "youtube"
-        for post in &mut filtered_posts {
-            post.feed_id = self.config.id.clone();
-        }
+        Ok(self.select_posts(posts, false, "YouTube videos"))
+    }
 
-        // Sort the filtered posts by publication date (oldest first) for correct publication order
-        // This ensures that if there are 2 new videos, the older one is published first
-        filtered_posts.sort_by_key(|a| a.published);
+    fn parse_rss_posts(&mut self, channel: rss::Channel) -> Result<Vec<Post>> {
+        let mut posts = Vec::new();
 
-        log::info!(
-            "Selected {} YouTube videos for publishing from feed: {} (ordered for publication)",
-            filtered_posts.len(),
-            self.config.name
-        );
+        for item in channel.items() {
+            if let Some(mut post) = Post::from_rss_item(item, self.config.id.clone()) {
+                if post.language.is_none() {
+                    post.language = channel.language().map(str::to_string);
+                }
 
-        for (i, post) in filtered_posts.iter().enumerate() {
-            log::info!(
-                "Will publish #{}: '{}' ({})",
-                i + 1,
-                post.title,
-                post.published
-            );
+                posts.push(post);
+            }
         }
 
-        Ok(filtered_posts)
+        Ok(self.select_posts(posts, true, "RSS posts"))
     }
 
-    fn parse_rss_posts(&mut self, channel: rss::Channel) -> Result<Vec<Post>> {
+    fn parse_feed_rs_posts(&mut self, feed: feed_rs::model::Feed) -> Result<Vec<Post>> {
         let mut posts = Vec::new();
-        let mut latest_date: Option<DateTime<Utc>> = None;
-
-        for item in channel.items() {
-            if let Some(post) = Post::from_rss_item(item, self.config.id.clone()) {
-                // Update latest date
-                if latest_date.is_none() || post.published > latest_date.unwrap() {
-                    latest_date = Some(post.published);
-                }
 
-                // Only include posts newer than our last check (if we have one)
-                if let Some(last_post_date) = self.last_post_date {
-                    if post.published <= last_post_date {
-                        continue;
-                    }
+        for entry in feed.entries {
+            if let Some(mut post) = Post::from_feed_item(&entry, self.config.id.clone()) {
+                if post.language.is_none() {
+                    post.language = feed.language.clone();
                 }
 
                 posts.push(post);
             }
         }
 
-        // Update last post date
-        if let Some(latest) = latest_date {
-            self.last_post_date = Some(latest);
-        }
-
-        // Sort posts by publication date (newest first) to get the latest ones
-        posts.sort_by_key(|b| std::cmp::Reverse(b.published));
-        // Limit to only the 2 most recent posts
-        posts.truncate(2);
+        Ok(self.select_posts(posts, true, "feed posts"))
+    }
 
-        log::info!("Available posts after sorting:");
-        for (i, post) in posts.iter().enumerate() {
-            log::info!("  {}: '{}' ({})", i + 1, post.title, post.published);
+    /// Choose which of the parsed posts to publish: newer than the last post
+    /// seen (when `filter_seen`), not older than `max_post_age_hours`, and at
+    /// most `max_posts_per_check` of them, returned oldest first.
+    ///
+    /// By default the newest posts win and the rest are dropped. With
+    /// `publish_backlog` every missed post is published, oldest first; when
+    /// `max_posts_per_check` is also set, the remainder waits for the next
+    /// check.
+    ///
+    /// On the first check `on_first_run` decides: the newest posts, none
+    /// (see `fetch_all_posts`), or every post since a date, which is then
+    /// handled as a backlog.
+    fn select_posts(&mut self, mut posts: Vec<Post>, filter_seen: bool, kind: &str) -> Vec<Post> {
+        let previous_post_date = self.last_post_date;
+        let latest_date = posts.iter().map(|post| post.published).max();
+
+        if self.select_all {
+            self.seeded = true;
+            if let Some(latest) = latest_date {
+                self.last_post_date =
+                    Some(previous_post_date.map_or(latest, |previous| previous.max(latest)));
+            }
+            posts.sort_by_key(|post| post.published);
+            log::info!(
+                "Fetched all {} {} from feed: {}",
+                posts.len(),
+                kind,
+                self.config.name
+            );
+            return posts;
         }
 
-        // Sort the filtered posts by publication date (oldest first) for correct publication order
-        // This ensures that if there are multiple new posts, the older one is published first
-        posts.sort_by_key(|a| a.published);
+        let since = match self.config.on_first_run {
+            FirstRun::PublishSince(since) if self.is_first_run() => Some(since),
+            _ => None,
+        };
+        // A backlog only exists once we know what was seen before, or from
+        // the date given for the first check
+        let backlog =
+            since.is_some() || (self.config.publish_backlog && previous_post_date.is_some());
 
-        log::info!(
-            "Selected {} RSS posts for publishing from feed: {} (ordered for publication)",
-            posts.len(),
-            self.config.name
-        );
+        // Only include posts newer than our last check (if we have one). A
+        // backlog always starts there, even for sources that are not
+        // filtered otherwise, such as YouTube
+        if let (true, Some(last_post_date)) = (filter_seen || backlog, previous_post_date) {
+            posts.retain(|post| post.published > last_post_date);
+        }
 
-        for (i, post) in posts.iter().enumerate() {
+        if let Some(since) = since {
+            posts.retain(|post| post.published >= since);
             log::info!(
-                "Will publish #{}: '{}' ({})",
-                i + 1,
-                post.title,
-                post.published
+                "First check of feed {}: {} {} since {}",
+                self.config.name,
+                posts.len(),
+                kind,
+                since
             );
         }
 
-        Ok(posts)
-    }
+        if let Some(max_age_hours) = self.config.max_post_age_hours {
+            let cutoff = Utc::now() - chrono::Duration::hours(max_age_hours as i64);
+            let before = posts.len();
+            posts.retain(|post| post.published >= cutoff);
+            if posts.len() < before {
+                log::info!(
+                    "Skipped {} {} older than {} hours from feed: {}",
+                    before - posts.len(),
+                    kind,
+                    max_age_hours,
+                    self.config.name
+                );
+            }
+        }
 
-    fn parse_feed_rs_posts(&mut self, feed: feed_rs::model::Feed) -> Result<Vec<Post>> {
-        let mut posts = Vec::new();
-        let mut latest_date: Option<DateTime<Utc>> = None;
+        // Sort by publication date (newest first)
+        posts.sort_by_key(|post| std::cmp::Reverse(post.published));
 
-        for entry in feed.entries {
-            if let Some(post) = Post::from_feed_item(&entry, self.config.id.clone()) {
-                // Update latest date
-                if latest_date.is_none() || post.published > latest_date.unwrap() {
-                    latest_date = Some(post.published);
-                }
+        log::info!("Available {} after sorting:", kind);
+        for (i, post) in posts.iter().enumerate() {
+            log::info!("  {}: '{}' ({})", i + 1, post.title, post.published);
+        }
 
-                // Only include posts newer than our last check (if we have one)
-                if let Some(last_post_date) = self.last_post_date {
-                    if post.published <= last_post_date {
-                        continue;
-                    }
+        let mut backlog_left = false;
+        if backlog {
+            if let Some(max_posts) = self.config.max_posts_per_check {
+                if posts.len() > max_posts {
+                    // Keep the oldest ones; the newer ones wait for the next check
+                    posts.drain(..posts.len() - max_posts);
+                    backlog_left = true;
                 }
-
-                posts.push(post);
             }
+        } else {
+            posts.truncate(
+                self.config
+                    .max_posts_per_check
+                    .unwrap_or(DEFAULT_MAX_POSTS_PER_CHECK),
+            );
         }
 
-        // Update last post date
-        if let Some(latest) = latest_date {
-            self.last_post_date = Some(latest);
-        }
-
-        // Sort posts by publication date (newest first) to get the latest ones
-        posts.sort_by_key(|b| std::cmp::Reverse(b.published));
-        // Limit to only the 2 most recent posts
-        posts.truncate(2);
-
-        log::info!("Available posts after sorting:");
-        for (i, post) in posts.iter().enumerate() {
-            log::info!("  {}: '{}' ({})", i + 1, post.title, post.published);
+        if backlog_left {
+            // Only advance past what is published now, and drop the cache
+            // validators so the next check parses the feed again even if it
+            // has not changed
+            self.last_post_date = posts.first().map(|post| post.published);
+            self.cache_metadata = FeedCacheMetadata::default();
+            log::info!(
+                "Backlog of feed {} continues on the next check",
+                self.config.name
+            );
+        } else if let Some(latest) = latest_date {
+            self.last_post_date =
+                Some(previous_post_date.map_or(latest, |previous| previous.max(latest)));
         }
 
-        // Sort the filtered posts by publication date (oldest first) for correct publication order
+        // Sort the selected posts by publication date (oldest first) for correct publication order
         // This ensures that if there are multiple new posts, the older one is published first
-        posts.sort_by_key(|a| a.published);
+        posts.sort_by_key(|post| post.published);
 
         log::info!(
-            "Selected {} feed posts for publishing from feed: {} (ordered for publication)",
+            "Selected {} {} for publishing from feed: {} (ordered for publication)",
             posts.len(),
+            kind,
             self.config.name
         );
 
@@ -414,7 +503,7 @@ impl Feed {
             );
         }
 
-        Ok(posts)
+        posts
     }
 
     pub fn should_check(&self, default_interval_minutes: u64) -> bool {
@@ -427,10 +516,19 @@ impl Feed {
             .check_interval_minutes
             .unwrap_or(default_interval_minutes);
 
-        match self.last_checked {
-            None => true,
-            Some(last_checked) => {
-                let now = Utc::now();
+        let Some(last_checked) = self.last_checked else {
+            return true;
+        };
+        // The scheduler ticks on the minute and the check itself starts a
+        // moment later; count from the tick so intervals do not drift
+        let last_checked = last_checked
+            .duration_trunc(TimeDelta::minutes(1))
+            .unwrap_or(last_checked);
+        let now = Utc::now();
+
+        match &self.schedule {
+            Some(cron) => next_run(cron, last_checked, timezone()).is_some_and(|next| next <= now),
+            None => {
                 let duration_since_check = now.signed_duration_since(last_checked);
                 duration_since_check.num_minutes() >= interval_minutes as i64
             }
@@ -442,6 +540,14 @@ impl Feed {
     }
 }
 
+/// Invalid expressions are rejected when the configuration is validated
+fn parse_schedule(config: &FeedConfig) -> Option<Cron> {
+    config
+        .cron
+        .as_deref()
+        .and_then(|expression| parse_cron(expression).ok())
+}
+
 pub struct FeedManager {
     feeds: Vec<Feed>,
 }
@@ -487,9 +593,13 @@ impl FeedManager {
     pub fn get_cache_metadata(&self) -> crate::storage::FeedCacheStorage {
         let mut cache = crate::storage::FeedCacheStorage::default();
         for feed in &self.feeds {
-            cache
-                .feeds
-                .insert(feed.config.id.clone(), feed.cache_metadata.clone());
+            let metadata = FeedCacheMetadata {
+                last_checked: feed.last_checked,
+                last_post_date: feed.last_post_date,
+                seeded: feed.seeded,
+                ..feed.cache_metadata.clone()
+            };
+            cache.feeds.insert(feed.config.id.clone(), metadata);
         }
         cache
     }
@@ -501,9 +611,28 @@ impl FeedManager {
         let mut results = Vec::new();
 
         for feed in &mut self.feeds {
+            // Checked only by `fetch_all_posts`, see `first_run_to_mark_seen`
+            if feed.config.on_first_run == FirstRun::MarkAllSeen && feed.is_first_run() {
+                log::debug!(
+                    "Skipping feed (entries not yet marked as seen): {}",
+                    feed.config.name
+                );
+                continue;
+            }
+
             if feed.should_check(default_interval_minutes) {
                 log::info!("Checking feed: {}", feed.config.name);
+                let timer = metrics::FEED_FETCH_DURATION
+                    .with_label_values(&[&feed.config.id])
+                    .start_timer();
                 let result = feed.fetch_posts().await;
+                timer.observe_duration();
+                metrics::FEED_FETCHES
+                    .with_label_values(&[
+                        feed.config.id.as_str(),
+                        metrics::result_label(result.is_ok()),
+                    ])
+                    .inc();
                 results.push((feed.config.id.clone(), result));
             } else {
                 log::debug!("Skipping feed (not due for check): {}", feed.config.name);
@@ -513,6 +642,43 @@ impl FeedManager {
         results
     }
 
+    /// Whether any feed is due for a check
+    pub fn has_due_feeds(&self, default_interval_minutes: u64) -> bool {
+        self.feeds
+            .iter()
+            .any(|feed| feed.should_check(default_interval_minutes))
+    }
+
+    /// Feeds due for their first check that are set to `mark_all_seen`
+    pub fn first_run_to_mark_seen(&self, default_interval_minutes: u64) -> Vec<String> {
+        self.feeds
+            .iter()
+            .filter(|feed| {
+                feed.config.on_first_run == FirstRun::MarkAllSeen
+                    && feed.is_first_run()
+                    && feed.should_check(default_interval_minutes)
+            })
+            .map(|feed| feed.config.id.clone())
+            .collect()
+    }
+
+    /// Fetch every current entry of the given feeds, see `Feed::fetch_all_posts`
+    pub async fn fetch_all_posts(&mut self, ids: &[String]) -> Vec<(String, Result<Vec<Post>>)> {
+        let mut results = Vec::new();
+
+        for feed in self.feeds.iter_mut() {
+            if !ids.contains(&feed.config.id) {
+                continue;
+            }
+
+            log::info!("Fetching all entries of feed: {}", feed.config.name);
+            let result = feed.fetch_all_posts().await;
+            results.push((feed.config.id.clone(), result));
+        }
+
+        results
+    }
+
     pub fn get_feed(&self, id: &str) -> Option<&Feed> {
         self.feeds.iter().find(|f| f.config.id == id)
     }
@@ -541,3 +707,242 @@ impl FeedManager {
         self.feeds.iter().filter(|f| f.config.enabled).count()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Datelike;
+    use serde_json::{json, Value};
+
+    /// An RSS feed with the given settings on top of the required ones
+    fn feed(settings: Value) -> Feed {
+        let mut config = json!({
+            "id": "blog",
+            "type": "Rss",
+            "config": { "url": "https://example.com/feed.xml" },
+            "name": "Blog",
+            "enabled": true,
+            "publishers": []
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(settings.as_object().unwrap().clone());
+        Feed::new(serde_json::from_value(config).unwrap(), None)
+    }
+
+    /// Posts `p1`..`pN` published one hour apart, the last one an hour before
+    /// the start of the current hour
+    fn posts(count: i64) -> Vec<Post> {
+        let now = Utc::now().duration_trunc(TimeDelta::hours(1)).unwrap();
+        (1..=count)
+            .map(|i| {
+                Post::for_test(
+                    "blog",
+                    &format!("p{}", i),
+                    now - TimeDelta::hours(count - i + 1),
+                )
+            })
+            .collect()
+    }
+
+    fn guids(posts: &[Post]) -> Vec<&str> {
+        posts.iter().map(|post| post.guid.as_str()).collect()
+    }
+
+    #[test]
+    fn test_select_newest_posts() {
+        let mut default = feed(json!({}));
+        let selected = default.select_posts(posts(5), true, "posts");
+        // The newest two, published oldest first
+        assert_eq!(guids(&selected), ["p4", "p5"]);
+        assert_eq!(default.last_post_date, Some(posts(5)[4].published));
+
+        let mut capped = feed(json!({ "max_posts_per_check": 3 }));
+        let selected = capped.select_posts(posts(5), true, "posts");
+        assert_eq!(guids(&selected), ["p3", "p4", "p5"]);
+
+        // Posts up to the last one seen are skipped
+        capped.last_post_date = Some(posts(5)[3].published);
+        let selected = capped.select_posts(posts(5), true, "posts");
+        assert_eq!(guids(&selected), ["p5"]);
+    }
+
+    #[test]
+    fn test_select_backlog() {
+        let mut backlog = feed(json!({ "publish_backlog": true, "max_posts_per_check": 2 }));
+        let all = posts(6);
+        backlog.last_post_date = Some(all[0].published);
+
+        // The oldest missed posts go first and the rest waits
+        let selected = backlog.select_posts(all.clone(), true, "posts");
+        assert_eq!(guids(&selected), ["p2", "p3"]);
+        assert_eq!(backlog.last_post_date, Some(all[2].published));
+
+        let selected = backlog.select_posts(all.clone(), true, "posts");
+        assert_eq!(guids(&selected), ["p4", "p5"]);
+
+        let selected = backlog.select_posts(all.clone(), true, "posts");
+        assert_eq!(guids(&selected), ["p6"]);
+        assert_eq!(backlog.last_post_date, Some(all[5].published));
+
+        // Without a cap the whole backlog goes at once
+        let mut uncapped = feed(json!({ "publish_backlog": true }));
+        uncapped.last_post_date = Some(all[1].published);
+        let selected = uncapped.select_posts(all.clone(), true, "posts");
+        assert_eq!(guids(&selected), ["p3", "p4", "p5", "p6"]);
+
+        // Before anything was seen there is no backlog
+        let mut first = feed(json!({ "publish_backlog": true }));
+        let selected = first.select_posts(all, true, "posts");
+        assert_eq!(guids(&selected), ["p5", "p6"]);
+    }
+
+    #[test]
+    fn test_select_youtube_backlog() {
+        // YouTube videos are not filtered by date, except for a backlog
+        let mut youtube = feed(json!({ "publish_backlog": true }));
+        let all = posts(4);
+        youtube.last_post_date = Some(all[1].published);
+        let selected = youtube.select_posts(all.clone(), false, "videos");
+        assert_eq!(guids(&selected), ["p3", "p4"]);
+
+        let mut latest = feed(json!({ "max_posts_per_check": 3 }));
+        latest.last_post_date = Some(all[3].published);
+        let selected = latest.select_posts(all, false, "videos");
+        assert_eq!(guids(&selected), ["p2", "p3", "p4"]);
+    }
+
+    #[test]
+    fn test_select_max_post_age() {
+        // Posts are 1 to 5 hours old, counted from the start of this hour
+        let mut recent = feed(json!({ "max_post_age_hours": 4, "max_posts_per_check": 10 }));
+        let selected = recent.select_posts(posts(5), true, "posts");
+        assert_eq!(guids(&selected), ["p3", "p4", "p5"]);
+        // The skipped posts still count as seen
+        assert_eq!(recent.last_post_date, Some(posts(5)[4].published));
+    }
+
+    #[test]
+    fn test_select_since_first_run() {
+        let all = posts(6);
+        let since = all[2].published.to_rfc3339();
+        let mut capped = feed(json!({
+            "on_first_run": format!("publish_since:{}", since),
+            "max_posts_per_check": 2
+        }));
+
+        // The oldest posts since the date come first, even without publish_backlog
+        let selected = capped.select_posts(all.clone(), true, "posts");
+        assert_eq!(guids(&selected), ["p3", "p4"]);
+        assert_eq!(capped.last_post_date, Some(all[3].published));
+
+        // After the first check the date no longer applies
+        let selected = capped.select_posts(all.clone(), true, "posts");
+        assert_eq!(guids(&selected), ["p5", "p6"]);
+
+        let mut uncapped = feed(json!({ "on_first_run": format!("publish_since:{}", since) }));
+        let selected = uncapped.select_posts(all, true, "posts");
+        assert_eq!(guids(&selected), ["p3", "p4", "p5", "p6"]);
+    }
+
+    #[test]
+    fn test_empty_feed_seeded_once() {
+        let mut manager = FeedManager::new();
+        let config = feed(json!({ "on_first_run": "mark_all_seen" })).config;
+        manager.add_feed(config.clone(), None);
+
+        // Seeding a feed with no entries still ends its first run
+        let seeded = manager.get_feed_mut("blog").unwrap();
+        assert!(seeded.is_first_run());
+        seeded.select_all = true;
+        assert!(seeded.select_posts(Vec::new(), true, "posts").is_empty());
+        seeded.select_all = false;
+        assert!(!seeded.is_first_run());
+
+        // ...also after a restart
+        let cache = manager.get_cache_metadata();
+        let metadata = serde_json::to_string(&cache.feeds["blog"]).unwrap();
+        let metadata = serde_json::from_str(&metadata).unwrap();
+        let mut restored = Feed::new_with_cache(config, metadata, None);
+        assert!(!restored.is_first_run());
+        assert_eq!(manager.first_run_to_mark_seen(60), Vec::<String>::new());
+
+        // The first real post is published, not marked as seen
+        let selected = restored.select_posts(posts(1), true, "posts");
+        assert_eq!(guids(&selected), ["p1"]);
+    }
+
+    #[test]
+    fn test_should_check_interval_from_minute() {
+        let minute = Utc::now().duration_trunc(TimeDelta::minutes(1)).unwrap();
+        let mut feed = feed(json!({ "check_interval_minutes": 5 }));
+        assert!(feed.should_check(60));
+
+        // Checked 30s into a tick five minutes ago: counted from the tick
+        feed.last_checked = Some(minute - TimeDelta::minutes(5) + TimeDelta::seconds(30));
+        assert!(feed.should_check(60));
+
+        feed.last_checked = Some(minute - TimeDelta::minutes(4) + TimeDelta::seconds(30));
+        assert!(!feed.should_check(60));
+
+        feed.config.enabled = false;
+        feed.last_checked = None;
+        assert!(!feed.should_check(60));
+    }
+
+    #[test]
+    fn test_should_check_cron() {
+        let minute = Utc::now().duration_trunc(TimeDelta::minutes(1)).unwrap();
+
+        let mut every_minute = feed(json!({ "cron": "0 * * * * *", "check_interval_minutes": 60 }));
+        // The cron wins over check_interval_minutes
+        every_minute.last_checked = Some(minute - TimeDelta::minutes(1) + TimeDelta::seconds(30));
+        assert!(every_minute.should_check(60));
+        every_minute.last_checked = Some(Utc::now());
+        assert!(!every_minute.should_check(60));
+
+        // A yearly cron months away is not due even though the interval passed
+        let month = (Utc::now().month() + 5) % 12 + 1;
+        let mut yearly = feed(json!({
+            "cron": format!("0 0 0 1 {} *", month),
+            "check_interval_minutes": 1
+        }));
+        assert!(yearly.should_check(60));
+        yearly.last_checked = Some(minute - TimeDelta::minutes(10));
+        assert!(!yearly.should_check(60));
+    }
+
+    #[tokio::test]
+    async fn test_should_check_after_failed_fetch() {
+        // Nothing listens on the port once the listener is dropped
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/feed.xml", listener.local_addr().unwrap());
+        drop(listener);
+
+        let mut failing = feed(json!({
+            "config": { "url": url },
+            "check_interval_minutes": 5,
+            "max_retries": 0
+        }));
+        assert!(failing.fetch_posts().await.is_err());
+        assert!(failing.last_checked.is_some());
+        assert!(!failing.should_check(60));
+    }
+
+    #[tokio::test]
+    async fn test_should_check_youtube_feed() {
+        let mut youtube = feed(json!({
+            "type": "Youtube",
+            "config": { "channel_id": "UC123" },
+            "cron": "0 0 * * * *",
+            "max_retries": 0
+        }));
+        assert!(youtube.should_check(60));
+
+        // Without the global YouTube settings the fetch fails before any request
+        assert!(youtube.fetch_posts().await.is_err());
+        assert!(youtube.last_checked.is_some());
+        assert!(!youtube.should_check(60));
+    }
+}
@@ -0,0 +1,245 @@
+use crate::models::{TemplateContext, TemplateRenderer};
+use anyhow::Result;
+use regex::Regex;
+use std::sync::LazyLock;
+use unicode_segmentation::UnicodeSegmentation;
+
+static URL_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"https?://\S+").unwrap());
+
+const ELLIPSIS: &str = "…";
+
+/// How a platform counts the length of a post
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthRule {
+    /// User-perceived characters (extended grapheme clusters)
+    Graphemes,
+    /// Unicode code points
+    Chars,
+    /// Unicode code points, with every URL counted as a fixed length
+    CharsWithUrlWeight(usize),
+    /// UTF-16 code units (an emoji outside the BMP counts as 2)
+    Utf16,
+}
+
+/// Maximum post length on a platform and the rule used to measure it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthBudget {
+    pub max: usize,
+    pub rule: LengthRule,
+}
+
+impl LengthBudget {
+    /// X counts every link as 23 characters (t.co)
+    pub const X: Self = Self {
+        max: 280,
+        rule: LengthRule::CharsWithUrlWeight(23),
+    };
+    pub const BLUESKY: Self = Self {
+        max: 300,
+        rule: LengthRule::Graphemes,
+    };
+    /// Mastodon counts every link as 23 characters, whatever its length
+    pub const MASTODON: Self = Self {
+        max: 500,
+        rule: LengthRule::CharsWithUrlWeight(23),
+    };
+    pub const THREADS: Self = Self {
+        max: 500,
+        rule: LengthRule::Chars,
+    };
+    pub const TELEGRAM_MESSAGE: Self = Self {
+        max: 4096,
+        rule: LengthRule::Utf16,
+    };
+    pub const TELEGRAM_CAPTION: Self = Self {
+        max: 1024,
+        rule: LengthRule::Utf16,
+    };
+
+    pub fn length(&self, text: &str) -> usize {
+        match self.rule {
+            LengthRule::Graphemes => text.graphemes(true).count(),
+            LengthRule::Chars => text.chars().count(),
+            LengthRule::CharsWithUrlWeight(url_weight) => {
+                let urls = URL_REGEX.find_iter(text).collect::<Vec<_>>();
+                let url_chars: usize = urls.iter().map(|url| url.as_str().chars().count()).sum();
+                text.chars().count() - url_chars + urls.len() * url_weight
+            }
+            LengthRule::Utf16 => text.encode_utf16().count(),
+        }
+    }
+
+    pub fn fits(&self, text: &str) -> bool {
+        self.length(text) <= self.max
+    }
+
+    /// Render a template within the budget. When the result is too long the
+    /// description, then the content, then the title are shortened and the
+    /// template is rendered again, so the URL and the rest of the template
+    /// stay intact. Only as a last resort is the rendered text cut.
+    pub fn render(
+        &self,
+        renderer: &TemplateRenderer,
+        template: &str,
+        context: &TemplateContext,
+    ) -> Result<String> {
+        let text = self.render_shortened(renderer, template, context)?;
+        if self.fits(&text) {
+            return Ok(text);
+        }
+
+        log::warn!(
+            "Rendered text still exceeds {} characters after shortening, cutting it",
+            self.max
+        );
+        Ok(self.truncate(&text))
+    }
+
+    /// Like [`render`](Self::render), but the rendered text is never cut, so
+    /// markup written in the template stays balanced. The result can still
+    /// exceed the budget when the rest of the template is too long.
+    pub fn render_shortened(
+        &self,
+        renderer: &TemplateRenderer,
+        template: &str,
+        context: &TemplateContext,
+    ) -> Result<String> {
+        let mut text = renderer.render(template, context)?;
+        if self.fits(&text) {
+            return Ok(text);
+        }
+
+        let mut context = context.clone();
+        let fields: [fn(&mut TemplateContext) -> &mut String; 3] = [
+            |context| &mut context.description,
+            |context| &mut context.content,
+            |context| &mut context.title,
+        ];
+
+        for field in fields {
+            let original = field(&mut context).clone();
+            let graphemes = original.graphemes(true).count();
+            if graphemes == 0 {
+                continue;
+            }
+
+            let mut render_with = |length: usize| -> Result<String> {
+                *field(&mut context) = shorten(&original, length);
+                renderer.render(template, &context)
+            };
+
+            // Without this field the text is still too long: drop it and
+            // move on to the next one
+            text = render_with(0)?;
+            if !self.fits(&text) {
+                continue;
+            }
+
+            // Binary search for the longest version of the field that fits
+            let (mut low, mut high) = (0, (graphemes - 1).min(self.max));
+            while low < high {
+                let mid = (low + high).div_ceil(2);
+                if self.fits(&render_with(mid)?) {
+                    low = mid;
+                } else {
+                    high = mid - 1;
+                }
+            }
+
+            return render_with(low);
+        }
+
+        Ok(text)
+    }
+
+    /// Cut text to the budget on a grapheme boundary
+    pub fn truncate(&self, text: &str) -> String {
+        if self.fits(text) {
+            return text.to_string();
+        }
+
+        let graphemes = text.graphemes(true).collect::<Vec<_>>();
+        let (mut low, mut high) = (0, graphemes.len());
+        // Longest prefix that fits together with the ellipsis
+        while low < high {
+            let mid = (low + high).div_ceil(2);
+            let candidate = format!("{}{}", graphemes[..mid].concat(), ELLIPSIS);
+            if self.fits(&candidate) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+
+        format!("{}{}", graphemes[..low].concat().trim_end(), ELLIPSIS)
+    }
+}
+
+/// Shorten text to at most `max_graphemes` (ellipsis included), preferring
+/// to cut at a word boundary
+pub fn shorten(text: &str, max_graphemes: usize) -> String {
+    let graphemes = text.graphemes(true).collect::<Vec<_>>();
+    if graphemes.len() <= max_graphemes {
+        return text.to_string();
+    }
+    if max_graphemes == 0 {
+        return String::new();
+    }
+
+    let cut = graphemes[..max_graphemes - 1].concat();
+    // Only back off to a space if it does not lose too much text
+    let cut = match cut.rfind(char::is_whitespace) {
+        Some(space) if space > cut.len() / 2 => &cut[..space],
+        _ => cut.as_str(),
+    };
+
+    format!("{}{}", cut.trim_end(), ELLIPSIS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_length_rules() {
+        let text = "Café 👩‍👩‍👧 https://example.com/a/very/long/path/to/an/article";
+        // "Café " + emoji + " " + 50 character URL
+        assert_eq!(LengthBudget::BLUESKY.length(text), 5 + 1 + 1 + 50);
+        // The family emoji is 5 code points
+        assert_eq!(LengthBudget::THREADS.length(text), 5 + 5 + 1 + 50);
+        assert_eq!(LengthBudget::X.length(text), 5 + 5 + 1 + 23);
+    }
+
+    #[test]
+    fn test_shorten_multibyte() {
+        assert_eq!(shorten("añadir más ñandúes", 12), "añadir más…");
+        assert_eq!(shorten("🎉🎉🎉🎉", 3), "🎉🎉…");
+        assert_eq!(shorten("corto", 10), "corto");
+    }
+
+    #[test]
+    fn test_render_keeps_url() {
+        let budget = LengthBudget {
+            max: 60,
+            rule: LengthRule::CharsWithUrlWeight(23),
+        };
+        let context = TemplateContext {
+            title: "Título".to_string(),
+            description: "Descripción muy larga con acentos y emoji 🎉 ".repeat(10),
+            url: "https://example.com/a/very/long/path/to/an/article".to_string(),
+            ..Default::default()
+        };
+
+        let text = budget
+            .render(
+                &TemplateRenderer::new(),
+                "{{ title }}\n{{ description }}\n{{ url }}",
+                &context,
+            )
+            .unwrap();
+
+        assert!(budget.fits(&text));
+        assert!(text.starts_with("Título\nDescripción"));
+        assert!(text.ends_with("…\nhttps://example.com/a/very/long/path/to/an/article"));
+    }
+}
@@ -1,13 +1,21 @@
 pub mod config;
 pub mod feed;
+pub mod length;
+pub mod opengraph;
 pub mod post;
 pub mod publishers;
+pub mod retry;
+pub mod schedule;
 pub mod template;
 pub mod youtube;
 
 pub use config::*;
 pub use feed::*;
+pub use length::*;
+pub use opengraph::*;
 pub use post::*;
 pub use publishers::*;
+pub use retry::*;
+pub use schedule::*;
 pub use template::*;
 pub use youtube::*;
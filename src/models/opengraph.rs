@@ -0,0 +1,259 @@
+use anyhow::Result;
+use regex::Regex;
+use reqwest::Client;
+use std::sync::LazyLock;
+use url::Url;
+
+static META_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<meta\s[^>]*>").unwrap());
+static ATTR_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?s)([A-Za-z][\w:-]*)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+static TITLE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap());
+
+/// Pages larger than this are not read for metadata
+const MAX_PAGE_BYTES: usize = 2 * 1024 * 1024;
+
+/// OpenGraph metadata of a web page, with Twitter card and `<title>` fallbacks
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpenGraph {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Absolute image URL
+    pub image: Option<String>,
+}
+
+impl OpenGraph {
+    /// Fetch and parse a page. The declared `Content-Length` is checked first
+    /// and the body is read in chunks, so an oversized page is never fully buffered.
+    pub async fn fetch(client: &Client, url: &str) -> Result<Self> {
+        let mut response = client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Failed to fetch {}: {}",
+                url,
+                response.status()
+            ));
+        }
+
+        let too_large = |size: u64| {
+            anyhow::anyhow!(
+                "Page {} is too large to read metadata: {} bytes or more",
+                url,
+                size
+            )
+        };
+        if let Some(length) = response.content_length() {
+            if length > MAX_PAGE_BYTES as u64 {
+                return Err(too_large(length));
+            }
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            bytes.extend_from_slice(&chunk);
+            if bytes.len() > MAX_PAGE_BYTES {
+                return Err(too_large(bytes.len() as u64));
+            }
+        }
+
+        Ok(Self::parse(&String::from_utf8_lossy(&bytes), url))
+    }
+
+    /// Parse metadata from HTML; relative image URLs are resolved against `page_url`
+    pub fn parse(html: &str, page_url: &str) -> Self {
+        let mut og_title = None;
+        let mut og_description = None;
+        let mut og_image = None;
+        let mut twitter_title = None;
+        let mut twitter_description = None;
+        let mut twitter_image = None;
+        let mut meta_description = None;
+
+        for meta in META_REGEX.find_iter(html) {
+            let mut key = None;
+            let mut content = None;
+            for attr in ATTR_REGEX.captures_iter(meta.as_str()) {
+                let value = attr.get(2).or(attr.get(3)).map_or("", |m| m.as_str());
+                match attr[1].to_ascii_lowercase().as_str() {
+                    "property" | "name" => key = Some(value.to_ascii_lowercase()),
+                    "content" => content = Some(decode_entities(value.trim())),
+                    _ => {}
+                }
+            }
+
+            let (Some(key), Some(content)) = (key, content) else {
+                continue;
+            };
+            if content.is_empty() {
+                continue;
+            }
+
+            let slot = match key.as_str() {
+                "og:title" => &mut og_title,
+                "og:description" => &mut og_description,
+                "og:image" | "og:image:url" | "og:image:secure_url" => &mut og_image,
+                "twitter:title" => &mut twitter_title,
+                "twitter:description" => &mut twitter_description,
+                "twitter:image" | "twitter:image:src" => &mut twitter_image,
+                "description" => &mut meta_description,
+                _ => continue,
+            };
+            slot.get_or_insert(content);
+        }
+
+        let html_title = TITLE_REGEX
+            .captures(html)
+            .map(|caps| decode_entities(caps[1].trim()))
+            .filter(|title| !title.is_empty());
+
+        let image = og_image.or(twitter_image).and_then(|image| {
+            Url::parse(page_url)
+                .and_then(|base| base.join(&image))
+                .map(|url| url.to_string())
+                .ok()
+        });
+
+        Self {
+            title: og_title.or(twitter_title).or(html_title),
+            description: og_description.or(twitter_description).or(meta_description),
+            image,
+        }
+    }
+}
+
+/// Decode the HTML entities commonly found in attribute values
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => {
+                    if let Some(hex) = entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                        u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+                    } else if let Some(dec) = entity.strip_prefix('#') {
+                        dec.parse().ok().and_then(char::from_u32)
+                    } else {
+                        None
+                    }
+                }
+            };
+            ch.map(|ch| (ch, end))
+        });
+
+        match decoded {
+            Some((ch, end)) => {
+                result.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_parse_opengraph() {
+        let html = r#"<html><head>
+            <title>Fallback</title>
+            <meta content="Caf&eacute; &amp; m&#225;s" property="og:title">
+            <meta name="twitter:description" content='Twitter &quot;desc&quot;'>
+            <meta property="og:image" content="/img/cover.png" />
+        </head></html>"#;
+
+        let og = OpenGraph::parse(html, "https://example.com/blog/post");
+        // Unknown named entities are kept as they are
+        assert_eq!(og.title.as_deref(), Some("Caf&eacute; & más"));
+        assert_eq!(og.description.as_deref(), Some("Twitter \"desc\""));
+        assert_eq!(
+            og.image.as_deref(),
+            Some("https://example.com/img/cover.png")
+        );
+    }
+
+    #[test]
+    fn test_parse_title_fallback() {
+        let og = OpenGraph::parse("<title> Solo título </title>", "https://example.com");
+        assert_eq!(og.title.as_deref(), Some("Solo título"));
+        assert_eq!(og.description, None);
+        assert_eq!(og.image, None);
+    }
+
+    /// Serve each canned HTTP response once, in order, and return the base URL
+    async fn serve(responses: Vec<Vec<u8>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream.write_all(&response).await;
+            }
+        });
+        url
+    }
+
+    fn response(length: Option<usize>, body: &[u8]) -> Vec<u8> {
+        let mut head =
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nConnection: close\r\n".to_string();
+        if let Some(length) = length {
+            head.push_str(&format!("Content-Length: {}\r\n", length));
+        }
+        head.push_str("\r\n");
+        [head.into_bytes(), body.to_vec()].concat()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rejects_oversized_pages() {
+        let client = Client::new();
+        let page = b"<html><head><title>Small</title></head></html>";
+        let url = serve(vec![
+            response(Some(page.len()), page),
+            // A declared length over the limit is rejected without reading the body
+            response(Some(MAX_PAGE_BYTES + 1), b""),
+            // Without Content-Length the body is counted while it is read
+            response(None, &vec![b' '; MAX_PAGE_BYTES + 1024]),
+        ])
+        .await;
+
+        let og = OpenGraph::fetch(&client, &url).await.unwrap();
+        assert_eq!(og.title.as_deref(), Some("Small"));
+
+        let error = OpenGraph::fetch(&client, &url).await.unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("{} bytes", MAX_PAGE_BYTES + 1)),
+            "{}",
+            error
+        );
+
+        let error = OpenGraph::fetch(&client, &url).await.unwrap_err();
+        assert!(error.to_string().contains("too large"), "{}", error);
+    }
+}
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Post {
@@ -9,6 +10,37 @@ pub struct Post {
     pub link: String,
     pub published: DateTime<Utc>,
     pub feed_id: String,
+    /// Lead image of the post, if the feed provides one
+    #[serde(default)]
+    pub image_url: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Categories, tags and keywords, without duplicates
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub enclosures: Vec<Enclosure>,
+    /// Full content (e.g. `content:encoded`), usually HTML
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Name and URL of the source feed, set by the feed
+    #[serde(default)]
+    pub feed_name: String,
+    #[serde(default)]
+    pub feed_url: String,
+    /// Hashtags from the categories after applying the feed's hashtag settings
+    #[serde(default)]
+    pub hashtags: Vec<String>,
+}
+
+/// Attached file (podcast audio, video, image...)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Enclosure {
+    pub url: String,
+    pub mime_type: Option<String>,
+    pub length: Option<u64>,
 }
 
 impl Post {
@@ -27,6 +59,15 @@ impl Post {
             link,
             published,
             feed_id,
+            image_url: None,
+            author: None,
+            categories: Vec::new(),
+            enclosures: Vec::new(),
+            content: None,
+            language: None,
+            feed_name: String::new(),
+            feed_url: String::new(),
+            hashtags: Vec::new(),
         }
     }
 
@@ -48,19 +89,99 @@ impl Post {
         };
 
         if !guid.is_empty() && !title.is_empty() && !link.is_empty() {
-            Some(Self::new(
-                guid,
-                title,
-                description,
-                link,
-                published,
-                feed_id,
-            ))
+            let mut post = Self::new(guid, title, description, link, published, feed_id);
+            post.image_url = Self::extract_rss_image(item);
+            post.author = Self::extract_rss_author(item);
+            post.categories = Self::extract_rss_categories(item);
+            post.enclosures = item
+                .enclosure()
+                .filter(|enclosure| !enclosure.url().is_empty())
+                .map(|enclosure| Enclosure {
+                    url: enclosure.url().to_string(),
+                    mime_type: non_empty(enclosure.mime_type()),
+                    length: enclosure.length().trim().parse().ok(),
+                })
+                .into_iter()
+                .collect();
+            post.content = item.content().and_then(non_empty);
+            // Item level dc:language; the feed fills in the channel language otherwise
+            post.language = item
+                .dublin_core_ext()
+                .and_then(|dc| dc.languages().first())
+                .and_then(|language| non_empty(language));
+            Some(post)
         } else {
             None
         }
     }
 
+    fn extract_rss_image(item: &rss::Item) -> Option<String> {
+        // Image enclosures first, then media:thumbnail, media:content and itunes:image
+        if let Some(enclosure) = item.enclosure() {
+            if enclosure.mime_type().starts_with("image/") && !enclosure.url().is_empty() {
+                return Some(enclosure.url().to_string());
+            }
+        }
+
+        let thumbnail = rss_media_elements(item, "thumbnail")
+            .into_iter()
+            .find_map(|thumbnail| thumbnail.attrs().get("url").and_then(|url| non_empty(url)));
+        if thumbnail.is_some() {
+            return thumbnail;
+        }
+
+        let media_image = rss_media_elements(item, "content")
+            .into_iter()
+            .filter(|content| {
+                content.attrs().get("medium").map(String::as_str) == Some("image")
+                    || content
+                        .attrs()
+                        .get("type")
+                        .is_some_and(|mime_type| mime_type.starts_with("image/"))
+            })
+            .find_map(|content| content.attrs().get("url").and_then(|url| non_empty(url)));
+        if media_image.is_some() {
+            return media_image;
+        }
+
+        item.itunes_ext()
+            .and_then(|itunes| itunes.image())
+            .and_then(non_empty)
+    }
+
+    fn extract_rss_author(item: &rss::Item) -> Option<String> {
+        // dc:creator usually holds a name, while <author> is an email address
+        item.dublin_core_ext()
+            .and_then(|dc| dc.creators().first())
+            .and_then(|creator| non_empty(creator))
+            .or_else(|| item.author().and_then(non_empty))
+            .or_else(|| {
+                item.itunes_ext()
+                    .and_then(|itunes| itunes.author())
+                    .and_then(non_empty)
+            })
+    }
+
+    fn extract_rss_categories(item: &rss::Item) -> Vec<String> {
+        let mut categories = Vec::new();
+
+        for category in item.categories() {
+            push_unique(&mut categories, category.name());
+        }
+        if let Some(dc) = item.dublin_core_ext() {
+            for subject in dc.subjects() {
+                push_unique(&mut categories, subject);
+            }
+        }
+        if let Some(keywords) = item.itunes_ext().and_then(|itunes| itunes.keywords()) {
+            for keyword in keywords.split(',') {
+                push_unique(&mut categories, keyword);
+            }
+        }
+
+        categories
+    }
+
     fn extract_rss_description(item: &rss::Item) -> Option<String> {
         // Try description field first (standard RSS description)
         if let Some(desc) = item.description() {
@@ -69,9 +190,10 @@ impl Post {
             }
         }
 
-        // For now, just use the standard description
-        // TODO: Add extension support for content:encoded and media:description later
-        None
+        // Then media:description, used by video feeds
+        rss_media_elements(item, "description")
+            .into_iter()
+            .find_map(|description| description.value().and_then(non_empty))
     }
 
     pub fn from_feed_item(item: &feed_rs::model::Entry, feed_id: String) -> Option<Self> {
@@ -85,23 +207,90 @@ impl Post {
         let published = item.published.or(item.updated).unwrap_or_else(Utc::now);
 
         if !guid.is_empty() && !title.is_empty() && !link.is_empty() {
-            Some(Self::new(
-                guid,
-                title,
-                description,
-                link,
-                published,
-                feed_id,
-            ))
+            let mut post = Self::new(guid, title, description, link, published, feed_id);
+            post.image_url = Self::extract_image(item);
+            post.author = item
+                .authors
+                .first()
+                .and_then(|author| non_empty(&author.name));
+            post.categories = Self::extract_categories(item);
+            post.enclosures = Self::extract_enclosures(item);
+            post.content = item
+                .content
+                .as_ref()
+                .and_then(|content| content.body.as_deref())
+                .and_then(non_empty);
+            post.language = item.language.as_deref().and_then(non_empty);
+            Some(post)
         } else {
             None
         }
     }
 
+    fn extract_image(item: &feed_rs::model::Entry) -> Option<String> {
+        // media:thumbnail (YouTube feeds use it), media:content images, then image enclosures
+        let thumbnail = item
+            .media
+            .iter()
+            .flat_map(|media| &media.thumbnails)
+            .map(|thumbnail| thumbnail.image.uri.clone())
+            .next();
+
+        let media_image = || {
+            item.media
+                .iter()
+                .flat_map(|media| &media.content)
+                .filter(|content| {
+                    content
+                        .content_type
+                        .as_ref()
+                        .is_some_and(|mime_type| mime_type.ty() == "image")
+                })
+                .find_map(|content| content.url.as_ref().map(|url| url.to_string()))
+        };
+
+        thumbnail.or_else(media_image).or_else(|| {
+            item.links
+                .iter()
+                .find(|link| {
+                    link.rel.as_deref() == Some("enclosure")
+                        && link
+                            .media_type
+                            .as_deref()
+                            .is_some_and(|media_type| media_type.starts_with("image/"))
+                })
+                .map(|link| link.href.clone())
+        })
+    }
+
+    fn extract_categories(item: &feed_rs::model::Entry) -> Vec<String> {
+        let mut categories = Vec::new();
+        for category in &item.categories {
+            push_unique(
+                &mut categories,
+                category.label.as_deref().unwrap_or(&category.term),
+            );
+        }
+        categories
+    }
+
+    fn extract_enclosures(item: &feed_rs::model::Entry) -> Vec<Enclosure> {
+        item.links
+            .iter()
+            .filter(|link| link.rel.as_deref() == Some("enclosure"))
+            .map(|link| Enclosure {
+                url: link.href.clone(),
+                mime_type: link.media_type.clone(),
+                length: link.length,
+            })
+            .collect()
+    }
+
     fn extract_description(item: &feed_rs::model::Entry) -> Option<String> {
         // Priority order for description extraction:
         // 1. Content (usually the full content - better for YouTube)
         // 2. Summary (Atom summary field)
+        // 3. media:description (YouTube feeds)
 
         // Try content first (usually more complete for YouTube)
         if let Some(content) = item.content.as_ref() {
@@ -119,7 +308,35 @@ impl Post {
             }
         }
 
-        None
+        item.media
+            .iter()
+            .filter_map(|media| media.description.as_ref())
+            .find_map(|description| non_empty(&description.content))
+    }
+}
+
+/// media:* elements of an RSS item, both direct children and inside media:group
+fn rss_media_elements<'a>(item: &'a rss::Item, name: &str) -> Vec<&'a rss::extension::Extension> {
+    let Some(media) = item.extensions().get("media") else {
+        return Vec::new();
+    };
+
+    let mut elements: Vec<_> = media.get(name).into_iter().flatten().collect();
+    for group in media.get("group").into_iter().flatten() {
+        elements.extend(group.children().get(name).into_iter().flatten());
+    }
+    elements
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn push_unique(values: &mut Vec<String>, value: &str) {
+    let value = value.trim();
+    if !value.is_empty() && !values.iter().any(|existing| existing == value) {
+        values.push(value.to_string());
     }
 }
 
@@ -149,6 +366,10 @@ impl PublishedPost {
         }
     }
 
+    pub fn key(&self) -> PostKey {
+        (self.feed_id.clone(), self.post_guid.clone())
+    }
+
     pub fn add_result(&mut self, publisher_id: String, success: bool, message: String) {
         self.publisher_results.push(PublisherResult {
             publisher_id,
@@ -159,10 +380,22 @@ impl PublishedPost {
     }
 }
 
+/// Key identifying a published post: (feed_id, post_guid)
+pub type PostKey = (String, String);
+
 /// Storage for tracking which posts have been published
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PublishedPostsStorage {
     pub posts: Vec<PublishedPost>,
+    /// Lookup index over `posts`, rebuilt after loading
+    #[serde(skip)]
+    index: HashSet<PostKey>,
+    /// Posts added or updated since the last save
+    #[serde(skip)]
+    changed: HashSet<PostKey>,
+    /// Posts removed since the last save
+    #[serde(skip)]
+    removed: HashSet<PostKey>,
 }
 
 impl PublishedPostsStorage {
@@ -170,10 +403,23 @@ impl PublishedPostsStorage {
         Self::default()
     }
 
+    pub fn from_posts(posts: Vec<PublishedPost>) -> Self {
+        let mut storage = Self {
+            posts,
+            ..Self::default()
+        };
+        storage.rebuild_index();
+        storage
+    }
+
+    /// Rebuild the lookup index, needed after deserializing `posts`
+    pub fn rebuild_index(&mut self) {
+        self.index = self.posts.iter().map(PublishedPost::key).collect();
+    }
+
     pub fn is_published(&self, post: &Post) -> bool {
-        self.posts
-            .iter()
-            .any(|p| p.post_guid == post.guid && p.feed_id == post.feed_id)
+        self.index
+            .contains(&(post.feed_id.clone(), post.guid.clone()))
     }
 
     pub fn mark_published(&mut self, post: &Post, results: Vec<(String, bool, String)>) {
@@ -183,9 +429,45 @@ impl PublishedPostsStorage {
             published_post.add_result(publisher_id, success, message);
         }
 
+        let key = published_post.key();
+        self.index.insert(key.clone());
+        self.removed.remove(&key);
+        self.changed.insert(key);
         self.posts.push(published_post);
     }
 
+    /// Replace the recorded outcome of one publisher for an already tracked
+    /// post. Returns false, with a warning, when the post is no longer
+    /// tracked, e.g. because cleanup removed it while a retry was pending.
+    pub fn update_result(
+        &mut self,
+        post: &Post,
+        publisher_id: &str,
+        success: bool,
+        message: String,
+    ) -> bool {
+        let Some(published_post) = self
+            .posts
+            .iter_mut()
+            .rfind(|p| p.post_guid == post.guid && p.feed_id == post.feed_id)
+        else {
+            log::warn!(
+                "Result of {} for \"{}\" not recorded: the post is no longer tracked ({})",
+                publisher_id,
+                post.title,
+                message
+            );
+            return false;
+        };
+
+        published_post
+            .publisher_results
+            .retain(|r| r.publisher_id != publisher_id);
+        published_post.add_result(publisher_id.to_string(), success, message);
+        self.changed.insert(published_post.key());
+        true
+    }
+
     #[allow(dead_code)]
     pub fn get_published_count(&self, feed_id: &str) -> usize {
         self.posts.iter().filter(|p| p.feed_id == feed_id).count()
@@ -193,6 +475,219 @@ impl PublishedPostsStorage {
 
     pub fn cleanup_old_posts(&mut self, days_to_keep: u64) {
         let cutoff_date = Utc::now() - chrono::Duration::days(days_to_keep as i64);
-        self.posts.retain(|p| p.published_at > cutoff_date);
+        let (kept, expired): (Vec<_>, Vec<_>) = self
+            .posts
+            .drain(..)
+            .partition(|p| p.published_at > cutoff_date);
+        self.posts = kept;
+
+        // A post can be tracked more than once; only forget it with its last entry
+        let kept_keys: HashSet<PostKey> = self.posts.iter().map(PublishedPost::key).collect();
+        for post in expired {
+            let key = post.key();
+            if kept_keys.contains(&key) {
+                continue;
+            }
+            self.index.remove(&key);
+            self.changed.remove(&key);
+            self.removed.insert(key);
+        }
+    }
+
+    /// Posts added or updated since the last save
+    pub fn changed_posts(&self) -> impl Iterator<Item = &PublishedPost> {
+        self.posts
+            .iter()
+            .filter(|p| self.changed.contains(&p.key()))
+    }
+
+    /// Posts removed since the last save
+    pub fn removed_keys(&self) -> impl Iterator<Item = &PostKey> {
+        self.removed.iter()
+    }
+
+    pub fn clear_pending_changes(&mut self) {
+        self.changed.clear();
+        self.removed.clear();
+    }
+}
+
+#[cfg(test)]
+impl Post {
+    /// A post with only the fields every feed provides
+    pub fn for_test(feed_id: &str, guid: &str, published: DateTime<Utc>) -> Self {
+        Self {
+            guid: guid.to_string(),
+            title: guid.to_string(),
+            description: None,
+            link: format!("https://example.com/{}", guid),
+            published,
+            feed_id: feed_id.to_string(),
+            image_url: None,
+            author: None,
+            categories: Vec::new(),
+            enclosures: Vec::new(),
+            content: None,
+            language: None,
+            feed_name: String::new(),
+            feed_url: String::new(),
+            hashtags: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_result_after_cleanup() {
+        let mut storage = PublishedPostsStorage::new();
+        let post = Post::for_test("feed", "guid", Utc::now());
+        storage.mark_published(&post, vec![("x".to_string(), false, "timeout".to_string())]);
+
+        assert!(storage.update_result(&post, "x", true, "ok".to_string()));
+        let results = &storage.posts[0].publisher_results;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].success);
+
+        // A retry finishing after cleanup removed the post is reported, not recorded
+        storage.posts[0].published_at = Utc::now() - chrono::Duration::days(40);
+        storage.cleanup_old_posts(30);
+        assert!(!storage.update_result(&post, "x", true, "ok".to_string()));
+        assert!(storage.posts.is_empty());
+        assert!(!storage.is_published(&post));
+    }
+
+    const RSS_FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"
+     xmlns:content="http://purl.org/rss/1.0/modules/content/"
+     xmlns:dc="http://purl.org/dc/elements/1.1/"
+     xmlns:media="http://search.yahoo.com/mrss/">
+  <channel>
+    <title>Blog</title>
+    <link>https://example.com/</link>
+    <description>Posts</description>
+    <item>
+      <title>First post</title>
+      <link>https://example.com/first</link>
+      <guid>first-guid</guid>
+      <pubDate>Fri, 01 Mar 2024 10:00:00 +0000</pubDate>
+      <description>Short summary</description>
+      <content:encoded><![CDATA[<p>Full <b>content</b></p>]]></content:encoded>
+      <author>ana@example.com (Ana)</author>
+      <dc:creator>Ana García</dc:creator>
+      <dc:language>es</dc:language>
+      <category>rust</category>
+      <category>linux</category>
+      <dc:subject>rust</dc:subject>
+      <dc:subject>self-hosting</dc:subject>
+      <enclosure url="https://example.com/episode.mp3" type="audio/mpeg" length="12345"/>
+    </item>
+    <item>
+      <title>Video</title>
+      <link>https://example.com/video</link>
+      <guid>video-guid</guid>
+      <media:group>
+        <media:content url="https://example.com/video.mp4" type="video/mp4"/>
+        <media:thumbnail url="https://example.com/thumb.jpg" width="480" height="360"/>
+        <media:description>Video description</media:description>
+      </media:group>
+    </item>
+  </channel>
+</rss>"#;
+
+    const ATOM_FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom"
+      xmlns:media="http://search.yahoo.com/mrss/">
+  <id>urn:channel</id>
+  <title>Channel</title>
+  <updated>2024-03-02T12:00:00Z</updated>
+  <entry>
+    <id>yt:video:abc123</id>
+    <title>Atom video</title>
+    <link rel="alternate" href="https://example.com/watch?v=abc123"/>
+    <link rel="enclosure" type="audio/ogg" length="999" href="https://example.com/audio.ogg"/>
+    <author><name>Channel Owner</name></author>
+    <published>2024-03-02T12:00:00Z</published>
+    <updated>2024-03-03T08:00:00Z</updated>
+    <category term="tech" label="Technology"/>
+    <category term="tech"/>
+    <content type="html" xml:lang="en">&lt;p&gt;Atom content&lt;/p&gt;</content>
+    <media:group>
+      <media:title>Atom video</media:title>
+      <media:thumbnail url="https://example.com/hq.jpg" width="480" height="360"/>
+      <media:description>Media description</media:description>
+    </media:group>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_from_rss_item() {
+        let channel = RSS_FEED.parse::<rss::Channel>().unwrap();
+        let posts: Vec<_> = channel
+            .items()
+            .iter()
+            .filter_map(|item| Post::from_rss_item(item, "blog".to_string()))
+            .collect();
+        assert_eq!(posts.len(), 2);
+
+        let post = &posts[0];
+        assert_eq!(post.guid, "first-guid");
+        assert_eq!(post.feed_id, "blog");
+        assert_eq!(post.published.to_rfc3339(), "2024-03-01T10:00:00+00:00");
+        assert_eq!(post.description.as_deref(), Some("Short summary"));
+        assert_eq!(post.content.as_deref(), Some("<p>Full <b>content</b></p>"));
+        // dc:creator wins over the <author> email address
+        assert_eq!(post.author.as_deref(), Some("Ana García"));
+        assert_eq!(post.categories, ["rust", "linux", "self-hosting"]);
+        assert_eq!(
+            post.enclosures,
+            [Enclosure {
+                url: "https://example.com/episode.mp3".to_string(),
+                mime_type: Some("audio/mpeg".to_string()),
+                length: Some(12345),
+            }]
+        );
+        assert_eq!(post.language.as_deref(), Some("es"));
+        // An audio enclosure is not an image
+        assert_eq!(post.image_url, None);
+
+        // media:group children are read like direct media:* elements
+        let video = &posts[1];
+        assert_eq!(
+            video.image_url.as_deref(),
+            Some("https://example.com/thumb.jpg")
+        );
+        assert_eq!(video.description.as_deref(), Some("Video description"));
+        assert!(video.enclosures.is_empty());
+    }
+
+    #[test]
+    fn test_from_feed_item() {
+        let feed = feed_rs::parser::parse(ATOM_FEED.as_bytes()).unwrap();
+        let post = Post::from_feed_item(&feed.entries[0], "channel".to_string()).unwrap();
+
+        assert_eq!(post.guid, "yt:video:abc123");
+        assert_eq!(post.link, "https://example.com/watch?v=abc123");
+        // published wins over updated
+        assert_eq!(post.published.to_rfc3339(), "2024-03-02T12:00:00+00:00");
+        assert_eq!(post.author.as_deref(), Some("Channel Owner"));
+        assert_eq!(post.categories, ["Technology", "tech"]);
+        assert_eq!(
+            post.enclosures,
+            [Enclosure {
+                url: "https://example.com/audio.ogg".to_string(),
+                mime_type: Some("audio/ogg".to_string()),
+                length: Some(999),
+            }]
+        );
+        assert_eq!(post.content.as_deref(), Some("<p>Atom content</p>"));
+        assert_eq!(post.description.as_deref(), Some("<p>Atom content</p>"));
+        assert_eq!(
+            post.image_url.as_deref(),
+            Some("https://example.com/hq.jpg")
+        );
+        assert_eq!(post.language.as_deref(), Some("en"));
     }
 }
@@ -1,18 +1,63 @@
+use super::image::download_image;
 use super::Publisher;
-use crate::models::{Post, TemplateContext, TemplateRenderer};
+use crate::models::{strip_html, LengthBudget, OpenGraph, Post, TemplateContext, TemplateRenderer};
+use crate::storage::write_atomic_private;
 use anyhow::Result;
 use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
 use regex::Regex;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tokio::sync::Mutex;
+use url::Url;
+
+/// Maximum size of a link card thumbnail in `app.bsky.embed.external`
+const MAX_THUMB_BYTES: usize = 1_000_000;
+/// Used when the PDS cannot be resolved from the handle
+const DEFAULT_PDS_URL: &str = "https://bsky.social";
+const HANDLE_RESOLVER_URL: &str = "https://public.api.bsky.app";
+const PLC_DIRECTORY_URL: &str = "https://plc.directory";
+/// Refresh the access token when it is this close to expiring
+const ACCESS_TOKEN_MARGIN_SECS: i64 = 60;
+
+static LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"https?://[^\s]+").unwrap());
+/// A handle after the start of the text, a space or an opening parenthesis
+static MENTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:^|[\s(])(@([a-zA-Z0-9](?:[a-zA-Z0-9-]*[a-zA-Z0-9])?(?:\.[a-zA-Z0-9](?:[a-zA-Z0-9-]*[a-zA-Z0-9])?)+))",
+    )
+    .unwrap()
+});
+static TAG_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)([#＃]([^\s#＃]+))").unwrap());
+/// Bluesky's limit for the length of a tag
+const MAX_TAG_CHARS: usize = 64;
+
+/// Tokens of an authenticated session, persisted between runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Session {
+    handle: String,
+    did: String,
+    pds_url: String,
+    access_jwt: String,
+    refresh_jwt: String,
+}
 
 pub struct BlueskyPublisher {
     #[allow(dead_code)]
     pub id: String,
     pub handle: String,
     pub password: String,
-    pub pds_url: String,
+    /// Resolved from the handle's DID document when not set
+    pub pds_url: Option<String>,
     pub template: String,
+    pub link_card: bool,
+    session_file: Option<PathBuf>,
+    session: Mutex<Option<Session>>,
     client: Client,
     renderer: TemplateRenderer,
 }
@@ -24,44 +69,172 @@ impl BlueskyPublisher {
         password: String,
         pds_url: Option<String>,
         template: String,
+        link_card: bool,
+        data_dir: Option<String>,
     ) -> Self {
+        let session_file =
+            data_dir.map(|dir| Path::new(&dir).join(format!("bluesky_session_{}.json", id)));
+
         Self {
             id,
             handle,
             password,
-            pds_url: pds_url.unwrap_or_else(|| "https://bsky.social".to_string()),
+            pds_url: pds_url.map(|url| url.trim_end_matches('/').to_string()),
             template,
+            link_card,
+            session_file,
+            session: Mutex::new(None),
             client: Client::new(),
             renderer: TemplateRenderer::new(),
         }
     }
 
-    fn extract_url_facets(&self, text: &str) -> Vec<Value> {
-        let url_regex = Regex::new(r"https?://[^\s]+").unwrap();
+    /// Detect links, `@handle` mentions and `#hashtags` as rich text
+    /// facets. Mentions whose handle cannot be resolved stay plain text.
+    async fn extract_facets(&self, text: &str, session: &Session) -> Vec<Value> {
         let mut facets = Vec::new();
 
-        for mat in url_regex.find_iter(text) {
-            let facet = json!({
-                "$type": "app.bsky.richtext.facet",
-                "index": {
-                    "byteStart": mat.start(),
-                    "byteEnd": mat.end()
-                },
-                "features": [
-                    {
-                        "$type": "app.bsky.richtext.facet#link",
-                        "uri": mat.as_str()
-                    }
-                ]
-            });
-            facets.push(facet);
+        for (start, end, uri) in find_links(text) {
+            facets.push(facet(
+                start,
+                end,
+                json!({ "$type": "app.bsky.richtext.facet#link", "uri": uri }),
+            ));
         }
 
+        for (start, end, handle) in find_mentions(text) {
+            match self.resolve_handle(&session.pds_url, handle).await {
+                Ok(did) => facets.push(facet(
+                    start,
+                    end,
+                    json!({ "$type": "app.bsky.richtext.facet#mention", "did": did }),
+                )),
+                Err(e) => log::debug!("Not linking mention @{}: {}", handle, e),
+            }
+        }
+
+        for (start, end, tag) in find_tags(text) {
+            facets.push(facet(
+                start,
+                end,
+                json!({ "$type": "app.bsky.richtext.facet#tag", "tag": tag }),
+            ));
+        }
+
+        facets.sort_by_key(|facet| facet["index"]["byteStart"].as_u64());
         facets
     }
 
-    async fn authenticate(&self) -> Result<(String, String)> {
-        let auth_url = format!("{}/xrpc/com.atproto.server.createSession", self.pds_url);
+    async fn resolve_handle(&self, base_url: &str, handle: &str) -> Result<String> {
+        let url = Url::parse_with_params(
+            &format!("{}/xrpc/com.atproto.identity.resolveHandle", base_url),
+            &[("handle", handle)],
+        )?;
+        let result: Value = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        result["did"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("No DID for handle {}", handle))
+    }
+
+    /// Return a session with a valid access token. Saved tokens are reused
+    /// and refreshed; the password is only used when there is no session or
+    /// the refresh fails.
+    async fn session(&self) -> Result<Session> {
+        let mut guard = self.session.lock().await;
+        if guard.is_none() {
+            *guard = self.load_session();
+        }
+
+        if let Some(session) = guard.as_ref() {
+            if !token_expired(&session.access_jwt) {
+                return Ok(session.clone());
+            }
+
+            match self.refresh_session(session).await {
+                Ok(refreshed) => {
+                    self.save_session(&refreshed);
+                    *guard = Some(refreshed.clone());
+                    return Ok(refreshed);
+                }
+                Err(e) => log::warn!("Bluesky session refresh failed, logging in again: {}", e),
+            }
+        }
+
+        let session = self.create_session().await?;
+        self.save_session(&session);
+        *guard = Some(session.clone());
+        Ok(session)
+    }
+
+    /// Forget the access token so the next call to `session` refreshes it
+    async fn expire_access_token(&self) {
+        if let Some(session) = self.session.lock().await.as_mut() {
+            session.access_jwt.clear();
+        }
+    }
+
+    fn load_session(&self) -> Option<Session> {
+        let path = self.session_file.as_ref()?;
+        if !path.exists() {
+            return None;
+        }
+
+        let session = match fs::read_to_string(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| Ok(serde_json::from_str::<Session>(&content)?))
+        {
+            Ok(session) => session,
+            Err(e) => {
+                log::warn!("Ignoring Bluesky session file {}: {}", path.display(), e);
+                return None;
+            }
+        };
+
+        // A different account or server in the config invalidates the session
+        let same_pds = self
+            .pds_url
+            .as_ref()
+            .is_none_or(|url| *url == session.pds_url);
+        (session.handle == self.handle && same_pds).then_some(session)
+    }
+
+    fn save_session(&self, session: &Session) {
+        let Some(path) = &self.session_file else {
+            return;
+        };
+
+        let result = serde_json::to_string_pretty(session)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| write_atomic_private(path, &content));
+        if let Err(e) = result {
+            log::warn!("Failed to save Bluesky session: {}", e);
+        }
+    }
+
+    async fn create_session(&self) -> Result<Session> {
+        let pds_url = match &self.pds_url {
+            Some(url) => url.clone(),
+            None => self.resolve_pds().await.unwrap_or_else(|e| {
+                log::warn!(
+                    "Could not resolve the PDS for {}, using {}: {}",
+                    self.handle,
+                    DEFAULT_PDS_URL,
+                    e
+                );
+                DEFAULT_PDS_URL.to_string()
+            }),
+        };
+
+        let auth_url = format!("{}/xrpc/com.atproto.server.createSession", pds_url);
 
         let payload = json!({
             "identifier": self.handle,
@@ -72,15 +245,12 @@ impl BlueskyPublisher {
 
         if response.status().is_success() {
             let result: Value = response.json().await?;
-            if let (Some(access_token), Some(did)) =
-                (result["accessJwt"].as_str(), result["did"].as_str())
-            {
-                Ok((access_token.to_string(), did.to_string()))
-            } else {
-                Err(anyhow::anyhow!(
-                    "Missing access token or DID in Bluesky auth response"
-                ))
-            }
+            // The DID document in the response names the account's own PDS
+            let pds_url = match &self.pds_url {
+                Some(_) => pds_url,
+                None => pds_from_did_document(&result["didDoc"]).unwrap_or(pds_url),
+            };
+            session_from_response(&self.handle, pds_url, &result)
         } else {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
@@ -91,75 +261,220 @@ impl BlueskyPublisher {
             ))
         }
     }
-}
 
-#[async_trait]
-impl Publisher for BlueskyPublisher {
-    async fn publish(&self, post: &Post) -> Result<String> {
-        // Authenticate first and get both access token and DID
-        let (access_token, did) = self.authenticate().await?;
+    async fn refresh_session(&self, session: &Session) -> Result<Session> {
+        let refresh_url = format!("{}/xrpc/com.atproto.server.refreshSession", session.pds_url);
 
-        let create_url = format!("{}/xrpc/com.atproto.repo.createRecord", self.pds_url);
+        let response = self
+            .client
+            .post(&refresh_url)
+            .bearer_auth(&session.refresh_jwt)
+            .send()
+            .await?;
 
-        let context = TemplateContext {
-            title: post.title.clone(),
-            description: post.description.clone().unwrap_or_default(),
-            url: post.link.clone(),
-        };
+        if response.status().is_success() {
+            let result: Value = response.json().await?;
+            session_from_response(&self.handle, session.pds_url.clone(), &result)
+        } else {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            Err(anyhow::anyhow!("{} - {}", status, error_text))
+        }
+    }
 
-        let text = self.renderer.render(&self.template, &context)?;
+    /// Find the handle's PDS: handle to DID, then the DID document's
+    /// `#atproto_pds` service
+    async fn resolve_pds(&self) -> Result<String> {
+        let did = if self.handle.starts_with("did:") {
+            self.handle.clone()
+        } else {
+            self.resolve_handle(HANDLE_RESOLVER_URL, &self.handle)
+                .await?
+        };
 
-        // Bluesky has a character limit of 300
-        let text = if text.len() > 300 {
-            format!("{}...", &text[..297])
+        let document_url = if did.starts_with("did:plc:") {
+            format!("{}/{}", PLC_DIRECTORY_URL, did)
+        } else if let Some(host) = did.strip_prefix("did:web:") {
+            format!("https://{}/.well-known/did.json", host)
         } else {
-            text
+            return Err(anyhow::anyhow!("Unsupported DID method: {}", did));
         };
 
-        let now = chrono::Utc::now().to_rfc3339();
+        let document: Value = self
+            .client
+            .get(&document_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
 
-        // Extract URL facets for automatic link detection
-        let facets = self.extract_url_facets(&text);
+        pds_from_did_document(&document)
+            .ok_or_else(|| anyhow::anyhow!("DID document for {} has no PDS service", did))
+    }
 
-        let mut record = json!({
-            "text": text,
-            "createdAt": now,
-            "$type": "app.bsky.feed.post"
+    /// Build an `app.bsky.embed.external` card for the post link from the
+    /// page's OpenGraph metadata, uploading its image as the thumbnail. The
+    /// card is still used without a thumbnail if the image cannot be uploaded
+    async fn build_link_card(&self, post: &Post, session: &Session) -> Result<Value> {
+        let og = OpenGraph::fetch(&self.client, &post.link).await?;
+
+        let mut external = json!({
+            "uri": post.link,
+            "title": og.title.unwrap_or_else(|| post.title.clone()),
+            "description": og
+                .description
+                .or_else(|| post.description.as_deref().map(strip_html))
+                .unwrap_or_default()
         });
 
-        // Add facets if any URLs were found
-        if !facets.is_empty() {
-            record["facets"] = json!(facets);
+        if let Some(image_url) = og.image.or_else(|| post.image_url.clone()) {
+            match self.upload_blob(&image_url, session).await {
+                Ok(blob) => external["thumb"] = blob,
+                Err(e) => log::warn!(
+                    "Bluesky link card for {} has no thumbnail: {}",
+                    post.link,
+                    e
+                ),
+            }
         }
 
+        Ok(json!({
+            "$type": "app.bsky.embed.external",
+            "external": external
+        }))
+    }
+
+    /// Create the post record. `Ok(None)` means the access token was rejected.
+    async fn create_record(&self, session: &Session, record: &Value) -> Result<Option<Value>> {
         let payload = json!({
-            "repo": did,  // Use DID instead of handle
+            "repo": session.did,  // Use DID instead of handle
             "collection": "app.bsky.feed.post",
             "record": record
         });
 
         let response = self
             .client
-            .post(&create_url)
-            .bearer_auth(&access_token)
+            .post(format!(
+                "{}/xrpc/com.atproto.repo.createRecord",
+                session.pds_url
+            ))
+            .bearer_auth(&session.access_jwt)
             .json(&payload)
             .send()
             .await?;
 
         if response.status().is_success() {
-            let result: Value = response.json().await?;
-            Ok(format!(
-                "Published to Bluesky: {}",
-                result["uri"].as_str().unwrap_or("unknown")
+            return Ok(Some(response.json().await?));
+        }
+
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        if status == StatusCode::UNAUTHORIZED
+            || error_text.contains("ExpiredToken")
+            || error_text.contains("InvalidToken")
+        {
+            log::info!(
+                "Bluesky rejected the access token: {} - {}",
+                status,
+                error_text
+            );
+            return Ok(None);
+        }
+        Err(anyhow::anyhow!(
+            "Failed to publish to Bluesky: {} - {}",
+            status,
+            error_text
+        ))
+    }
+
+    /// Download an image and upload it to the PDS, returning the blob reference
+    async fn upload_blob(&self, image_url: &str, session: &Session) -> Result<Value> {
+        let image = download_image(&self.client, image_url, MAX_THUMB_BYTES).await?;
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/xrpc/com.atproto.repo.uploadBlob",
+                session.pds_url
             ))
-        } else {
+            .bearer_auth(&session.access_jwt)
+            .header(reqwest::header::CONTENT_TYPE, image.content_type)
+            .body(image.bytes)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
-            Err(anyhow::anyhow!(
-                "Failed to publish to Bluesky: {} - {}",
+            return Err(anyhow::anyhow!(
+                "Failed to upload blob to Bluesky: {} - {}",
                 status,
                 error_text
-            ))
+            ));
+        }
+
+        let result: Value = response.json().await?;
+        match result.get("blob") {
+            Some(blob) if blob.is_object() => Ok(blob.clone()),
+            _ => Err(anyhow::anyhow!("Bluesky uploadBlob response has no blob")),
+        }
+    }
+}
+
+#[async_trait]
+impl Publisher for BlueskyPublisher {
+    async fn publish(&self, post: &Post) -> Result<String> {
+        let session = self.session().await?;
+
+        let context = TemplateContext::from_post(post, &self.id);
+
+        // Bluesky has a limit of 300 graphemes
+        let text = LengthBudget::BLUESKY.render(&self.renderer, &self.template, &context)?;
+
+        let now = chrono::Utc::now().to_rfc3339();
+
+        // Make links, mentions and hashtags clickable
+        let facets = self.extract_facets(&text, &session).await;
+
+        let mut record = json!({
+            "text": text,
+            "createdAt": now,
+            "$type": "app.bsky.feed.post"
+        });
+
+        // Add facets if any were found
+        if !facets.is_empty() {
+            record["facets"] = json!(facets);
+        }
+
+        // A card that cannot be built should not block the post itself
+        if self.link_card && !post.link.is_empty() {
+            match self.build_link_card(post, &session).await {
+                Ok(embed) => record["embed"] = embed,
+                Err(e) => log::warn!("Posting to Bluesky without link card: {}", e),
+            }
+        }
+
+        // A revoked or expired token is refreshed and the post sent once more
+        let mut result = self.create_record(&session, &record).await?;
+        if result.is_none() {
+            self.expire_access_token().await;
+            let session = self.session().await?;
+            result = self.create_record(&session, &record).await?;
+        }
+
+        match result {
+            Some(result) => Ok(format!(
+                "Published to Bluesky: {}",
+                result["uri"].as_str().unwrap_or("unknown")
+            )),
+            None => {
+                self.expire_access_token().await;
+                Err(anyhow::anyhow!(
+                    "Failed to publish to Bluesky: access token rejected after refreshing the session"
+                ))
+            }
         }
     }
 
@@ -175,3 +490,200 @@ impl Publisher for BlueskyPublisher {
         self
     }
 }
+
+fn session_from_response(handle: &str, pds_url: String, result: &Value) -> Result<Session> {
+    match (
+        result["did"].as_str(),
+        result["accessJwt"].as_str(),
+        result["refreshJwt"].as_str(),
+    ) {
+        (Some(did), Some(access_jwt), Some(refresh_jwt)) => Ok(Session {
+            handle: handle.to_string(),
+            did: did.to_string(),
+            pds_url,
+            access_jwt: access_jwt.to_string(),
+            refresh_jwt: refresh_jwt.to_string(),
+        }),
+        _ => Err(anyhow::anyhow!(
+            "Missing tokens or DID in Bluesky session response"
+        )),
+    }
+}
+
+fn pds_from_did_document(document: &Value) -> Option<String> {
+    document["service"].as_array()?.iter().find_map(|service| {
+        let is_pds = service["id"]
+            .as_str()
+            .is_some_and(|id| id.ends_with("#atproto_pds"));
+        is_pds
+            .then(|| service["serviceEndpoint"].as_str())
+            .flatten()
+            .map(|url| url.trim_end_matches('/').to_string())
+    })
+}
+
+/// Whether a JWT is expired or about to, judging by its `exp` claim.
+/// Tokens that cannot be decoded count as expired.
+fn token_expired(jwt: &str) -> bool {
+    let expires_at = jwt
+        .split('.')
+        .nth(1)
+        .and_then(|payload| {
+            general_purpose::URL_SAFE_NO_PAD
+                .decode(payload.trim_end_matches('='))
+                .ok()
+        })
+        .and_then(|claims| serde_json::from_slice::<Value>(&claims).ok())
+        .and_then(|claims| claims["exp"].as_i64());
+
+    match expires_at {
+        Some(exp) => exp - ACCESS_TOKEN_MARGIN_SECS <= chrono::Utc::now().timestamp(),
+        None => true,
+    }
+}
+
+fn facet(byte_start: usize, byte_end: usize, feature: Value) -> Value {
+    json!({
+        "$type": "app.bsky.richtext.facet",
+        "index": {
+            "byteStart": byte_start,
+            "byteEnd": byte_end
+        },
+        "features": [feature]
+    })
+}
+
+/// Links as `(byte_start, byte_end, uri)`, without trailing punctuation
+fn find_links(text: &str) -> Vec<(usize, usize, &str)> {
+    LINK_REGEX
+        .find_iter(text)
+        .map(|link| {
+            let mut uri = link
+                .as_str()
+                .trim_end_matches(['.', ',', ';', ':', '!', '?', '"', '\'']);
+            // Keep a closing parenthesis only when it belongs to the URL
+            while uri.ends_with(')') && uri.matches(')').count() > uri.matches('(').count() {
+                uri = &uri[..uri.len() - 1];
+            }
+            (link.start(), link.start() + uri.len(), uri)
+        })
+        .collect()
+}
+
+/// Mentions as `(byte_start, byte_end, handle)`; the range includes the `@`
+fn find_mentions(text: &str) -> Vec<(usize, usize, &str)> {
+    MENTION_REGEX
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let mention = caps.get(1)?;
+            let handle = caps.get(2)?.as_str();
+            // The last label of a handle is a TLD, never only digits
+            let tld = handle.rsplit('.').next()?;
+            (!tld.chars().all(|c| c.is_ascii_digit())).then_some((
+                mention.start(),
+                mention.end(),
+                handle,
+            ))
+        })
+        .collect()
+}
+
+/// Hashtags as `(byte_start, byte_end, tag)`; the range includes the `#`,
+/// the tag does not
+fn find_tags(text: &str) -> Vec<(usize, usize, &str)> {
+    TAG_REGEX
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let hashtag = caps.get(1)?;
+            let tag = caps
+                .get(2)?
+                .as_str()
+                .trim_end_matches(|c: char| c.is_ascii_punctuation() || c == '…');
+            if tag.is_empty()
+                || tag.chars().count() > MAX_TAG_CHARS
+                || tag.chars().all(|c| c.is_ascii_digit())
+            {
+                return None;
+            }
+            let prefix_len = hashtag.len() - caps.get(2)?.len();
+            Some((
+                hashtag.start(),
+                hashtag.start() + prefix_len + tag.len(),
+                tag,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_facet_byte_offsets() {
+        let text = "Café ñandú (https://example.com/a_(b)). Hola @ana.bsky.social, #Programación #2024 #rust…";
+
+        let links = find_links(text);
+        assert_eq!(links.len(), 1);
+        let (start, end, uri) = links[0];
+        assert_eq!(uri, "https://example.com/a_(b)");
+        assert_eq!(&text[start..end], uri);
+
+        let mentions = find_mentions(text);
+        assert_eq!(mentions.len(), 1);
+        let (start, end, handle) = mentions[0];
+        assert_eq!(handle, "ana.bsky.social");
+        assert_eq!(&text[start..end], "@ana.bsky.social");
+
+        let tags = find_tags(text)
+            .into_iter()
+            .map(|(start, end, tag)| (&text[start..end], tag))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tags,
+            vec![("#Programación", "Programación"), ("#rust", "rust")]
+        );
+    }
+
+    fn jwt(exp: i64) -> String {
+        let claims = general_purpose::URL_SAFE_NO_PAD.encode(json!({ "exp": exp }).to_string());
+        format!("eyJhbGciOiJFUzI1NksifQ.{}.signature", claims)
+    }
+
+    #[test]
+    fn test_token_expired() {
+        let now = chrono::Utc::now().timestamp();
+        assert!(!token_expired(&jwt(now + 3600)));
+        assert!(token_expired(&jwt(now - 10)));
+        // Tokens about to expire are refreshed ahead of time
+        assert!(token_expired(&jwt(now + ACCESS_TOKEN_MARGIN_SECS / 2)));
+        assert!(token_expired(""));
+        assert!(token_expired("not.a-jwt.at-all"));
+    }
+
+    #[test]
+    fn test_pds_from_did_document() {
+        let document = json!({
+            "id": "did:plc:abc",
+            "service": [
+                { "id": "#bsky_notif", "serviceEndpoint": "https://api.bsky.app" },
+                { "id": "#atproto_pds", "type": "AtprotoPersonalDataServer", "serviceEndpoint": "https://pds.example.com/" }
+            ]
+        });
+        assert_eq!(
+            pds_from_did_document(&document).as_deref(),
+            Some("https://pds.example.com")
+        );
+
+        let absolute_id = json!({
+            "service": [{ "id": "did:web:example.com#atproto_pds", "serviceEndpoint": "https://example.com" }]
+        });
+        assert_eq!(
+            pds_from_did_document(&absolute_id).as_deref(),
+            Some("https://example.com")
+        );
+
+        assert_eq!(pds_from_did_document(&json!({ "service": [] })), None);
+        assert_eq!(pds_from_did_document(&Value::Null), None);
+    }
+}
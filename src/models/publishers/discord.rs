@@ -1,39 +1,248 @@
 use super::Publisher;
-use crate::models::{Post, TemplateContext, TemplateRenderer};
+use crate::models::{shorten, DiscordImage, Post, TemplateContext, TemplateRenderer};
 use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::Client;
-use serde_json::json;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// Time before which each webhook must not be called again, shared by every
+/// publisher using the same webhook
+static RATE_LIMITS: LazyLock<Mutex<HashMap<String, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+/// Longest a single post waits for rate limits before failing; the retry
+/// queue picks it up from there
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(30);
+/// Most 429 responses a single post accepts before failing, however short
+/// the waits they ask for
+const MAX_RATE_LIMIT_ATTEMPTS: u32 = 5;
+/// Used when a 429 response does not say how long to wait
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+const WEBHOOK_HOSTS: [&str; 4] = [
+    "discord.com",
+    "discordapp.com",
+    "canary.discord.com",
+    "ptb.discord.com",
+];
+/// Discord limits for embed fields and thread names
+const MAX_EMBED_TITLE: usize = 256;
+const MAX_EMBED_DESCRIPTION: usize = 4096;
+const MAX_EMBED_FOOTER: usize = 2048;
+const MAX_EMBED_AUTHOR: usize = 256;
+const MAX_THREAD_NAME: usize = 100;
 
 pub struct DiscordPublisher {
     #[allow(dead_code)]
     pub id: String,
     pub webhook_url: String,
     pub template: String,
+    /// Send the post as an embed; `template` becomes the message content
+    pub embed: bool,
+    pub embed_description_template: String,
+    pub embed_image: DiscordImage,
+    pub color: Option<u32>,
+    pub username: Option<String>,
+    pub avatar_url: Option<String>,
+    pub thread_id: Option<String>,
+    /// Template for the title of a new forum post
+    pub thread_name: Option<String>,
     client: Client,
     renderer: TemplateRenderer,
 }
 
 impl DiscordPublisher {
-    pub fn new(id: String, webhook_url: String, template: String) -> Self {
-        Self {
+    #[expect(clippy::too_many_arguments)]
+    pub fn new(
+        id: String,
+        webhook_url: String,
+        template: String,
+        embed: bool,
+        embed_description_template: String,
+        embed_image: DiscordImage,
+        color: Option<&str>,
+        username: Option<String>,
+        avatar_url: Option<String>,
+        thread_id: Option<String>,
+        thread_name: Option<String>,
+    ) -> Result<Self> {
+        validate_webhook_url(&webhook_url)?;
+
+        if thread_id.is_some() && thread_name.is_some() {
+            return Err(anyhow::anyhow!(
+                "Discord publisher {} can set thread_id or thread_name, not both",
+                id
+            ));
+        }
+
+        let color = color.map(parse_color).transpose()?;
+
+        Ok(Self {
             id,
             webhook_url,
             template,
+            embed,
+            embed_description_template,
+            embed_image,
+            color,
+            username,
+            avatar_url,
+            thread_id,
+            thread_name,
             client: Client::new(),
             renderer: TemplateRenderer::new(),
+        })
+    }
+
+    fn build_embed(&self, post: &Post, context: &TemplateContext) -> Result<Value> {
+        let description = self
+            .renderer
+            .render(&self.embed_description_template, context)?;
+
+        let mut embed = json!({
+            "title": shorten(&post.title, MAX_EMBED_TITLE),
+            "url": post.link,
+            "description": shorten(&description, MAX_EMBED_DESCRIPTION),
+            "timestamp": post.published.to_rfc3339()
+        });
+
+        if let Some(color) = self.color {
+            embed["color"] = json!(color);
+        }
+        if let Some(author) = post.author.as_deref().filter(|author| !author.is_empty()) {
+            embed["author"] = json!({ "name": shorten(author, MAX_EMBED_AUTHOR) });
         }
+        if !post.feed_name.is_empty() {
+            embed["footer"] = json!({ "text": shorten(&post.feed_name, MAX_EMBED_FOOTER) });
+        }
+        if let Some(image_url) = &post.image_url {
+            match self.embed_image {
+                DiscordImage::Thumbnail => embed["thumbnail"] = json!({ "url": image_url }),
+                DiscordImage::Image => embed["image"] = json!({ "url": image_url }),
+                DiscordImage::None => {}
+            }
+        }
+
+        Ok(embed)
+    }
+
+    /// Webhook URL with `wait=true`, so Discord returns the message, and the
+    /// target thread if any
+    fn request_url(&self) -> Result<Url> {
+        let mut url = Url::parse(&self.webhook_url)?;
+        url.query_pairs_mut().append_pair("wait", "true");
+        if let Some(thread_id) = &self.thread_id {
+            url.query_pairs_mut().append_pair("thread_id", thread_id);
+        }
+        Ok(url)
+    }
+}
+
+fn rate_limit_wait(webhook_url: &str) -> Option<Duration> {
+    let limits = RATE_LIMITS.lock().unwrap();
+    let not_before = limits.get(webhook_url)?;
+    not_before.checked_duration_since(Instant::now())
+}
+
+fn set_rate_limit(webhook_url: &str, wait: Duration) {
+    RATE_LIMITS
+        .lock()
+        .unwrap()
+        .insert(webhook_url.to_string(), Instant::now() + wait);
+}
+
+fn header_seconds(headers: &HeaderMap, name: &str) -> Option<Duration> {
+    let seconds = headers
+        .get(name)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<f64>()
+        .ok()?;
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// When the bucket is exhausted, how long until it resets
+fn bucket_reset_after(headers: &HeaderMap) -> Option<Duration> {
+    let remaining = headers
+        .get("X-RateLimit-Remaining")?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    if remaining > 0 {
+        return None;
+    }
+    header_seconds(headers, "X-RateLimit-Reset-After")
+}
+
+/// How long to wait after a 429: the body's `retry_after`, then the
+/// `Retry-After` and `X-RateLimit-Reset-After` headers
+fn retry_after(headers: &HeaderMap, body: &Value) -> Duration {
+    body["retry_after"]
+        .as_f64()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .or_else(|| header_seconds(headers, "Retry-After"))
+        .or_else(|| header_seconds(headers, "X-RateLimit-Reset-After"))
+        .unwrap_or(DEFAULT_RETRY_AFTER)
+}
+
+/// Accept webhooks on any Discord host, e.g.
+/// https://discord.com/api/webhooks/WEBHOOK_ID/WEBHOOK_TOKEN
+fn validate_webhook_url(webhook_url: &str) -> Result<()> {
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid Discord webhook URL format. Expected: https://discord.com/api/webhooks/WEBHOOK_ID/WEBHOOK_TOKEN"
+        )
+    };
+
+    if webhook_url.is_empty() {
+        return Err(anyhow::anyhow!("Discord webhook URL is empty"));
     }
+
+    let url = Url::parse(webhook_url).map_err(|_| invalid())?;
+    let host_ok = url
+        .host_str()
+        .is_some_and(|host| WEBHOOK_HOSTS.contains(&host));
+    // The path may carry an API version: /api/v10/webhooks/...
+    let segments = url.path_segments().map(|s| s.collect::<Vec<_>>());
+    let path_ok = segments.is_some_and(|segments| {
+        let webhooks = segments.iter().position(|s| *s == "webhooks");
+        segments.first() == Some(&"api")
+            && webhooks.is_some_and(|i| i <= 2 && segments.len() >= i + 3)
+    });
+
+    if url.scheme() == "https" && host_ok && path_ok {
+        Ok(())
+    } else {
+        Err(invalid())
+    }
+}
+
+/// Parse a color as `#RRGGBB` or a decimal number
+fn parse_color(color: &str) -> Result<u32> {
+    let parsed = match color.strip_prefix('#') {
+        Some(hex) if hex.len() == 6 => u32::from_str_radix(hex, 16).ok(),
+        Some(_) => None,
+        None => color.parse::<u32>().ok().filter(|c| *c <= 0xFFFFFF),
+    };
+    parsed.ok_or_else(|| {
+        anyhow::anyhow!(
+            "Invalid Discord color '{}': expected #RRGGBB or a number",
+            color
+        )
+    })
 }
 
 #[async_trait]
 impl Publisher for DiscordPublisher {
     async fn publish(&self, post: &Post) -> Result<String> {
-        let context = TemplateContext {
-            title: post.title.clone(),
-            description: post.description.clone().unwrap_or_default(),
-            url: post.link.clone(),
-        };
+        let context = TemplateContext::from_post(post, &self.id);
 
         let message = self.renderer.render(&self.template, &context)?;
 
@@ -45,51 +254,93 @@ impl Publisher for DiscordPublisher {
             ));
         }
 
-        // Validate webhook URL format
-        if self.webhook_url.is_empty() {
-            return Err(anyhow::anyhow!("Discord webhook URL is empty"));
+        let mut payload = json!({});
+        if !message.is_empty() {
+            payload["content"] = json!(message);
         }
-
-        if !self
-            .webhook_url
-            .starts_with("https://discord.com/api/webhooks/")
-        {
-            return Err(anyhow::anyhow!(
-                "Invalid Discord webhook URL format. Expected: https://discord.com/api/webhooks/WEBHOOK_ID/WEBHOOK_TOKEN"
-            ));
+        if self.embed {
+            payload["embeds"] = json!([self.build_embed(post, &context)?]);
+        }
+        if let Some(username) = &self.username {
+            payload["username"] = json!(username);
+        }
+        if let Some(avatar_url) = &self.avatar_url {
+            payload["avatar_url"] = json!(avatar_url);
+        }
+        if let Some(thread_name) = &self.thread_name {
+            let thread_name = self.renderer.render(thread_name, &context)?;
+            payload["thread_name"] = json!(shorten(&thread_name, MAX_THREAD_NAME));
         }
-
-        let payload = json!({
-            "content": message,
-            "username": "RSS Bot"
-        });
 
         log::debug!(
             "Discord webhook URL (truncated): {}...",
             &self.webhook_url.chars().take(50).collect::<String>()
         );
 
-        let response = self
-            .client
-            .post(&self.webhook_url)
-            .header("Content-Type", "application/json")
-            .header("User-Agent", "Populatrs/1.0")
-            .json(&payload)
-            .send()
-            .await?;
+        let started = Instant::now();
+        let mut rate_limited = 0;
+        let response = loop {
+            if let Some(wait) = rate_limit_wait(&self.webhook_url) {
+                if started.elapsed() + wait > MAX_RATE_LIMIT_WAIT {
+                    return Err(anyhow::anyhow!(
+                        "Discord rate limit exceeded, next request allowed in {:.1}s",
+                        wait.as_secs_f64()
+                    ));
+                }
+                log::info!(
+                    "Discord rate limit reached, waiting {:.1}s",
+                    wait.as_secs_f64()
+                );
+                tokio::time::sleep(wait).await;
+            }
 
-        if response.status().is_success() {
-            Ok("Published to Discord via webhook".to_string())
-        } else {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
+            let response = self
+                .client
+                .post(self.request_url()?)
+                .header("Content-Type", "application/json")
+                .header("User-Agent", "Populatrs/1.0")
+                .json(&payload)
+                .send()
+                .await?;
+
+            let headers = response.headers().clone();
+            if response.status() != StatusCode::TOO_MANY_REQUESTS {
+                if let Some(reset_after) = bucket_reset_after(&headers) {
+                    set_rate_limit(&self.webhook_url, reset_after);
+                }
+                break response;
+            }
+
+            let body: Value = response.json().await.unwrap_or_default();
+            let retry_after = retry_after(&headers, &body);
+            log::warn!(
+                "Discord returned 429 for {}, retry after {:.1}s",
+                self.id,
+                retry_after.as_secs_f64()
+            );
+            set_rate_limit(&self.webhook_url, retry_after);
 
-            // Discord rate limiting
-            if status.as_u16() == 429 {
+            rate_limited += 1;
+            if rate_limited >= MAX_RATE_LIMIT_ATTEMPTS
+                || started.elapsed() + retry_after > MAX_RATE_LIMIT_WAIT
+            {
                 return Err(anyhow::anyhow!(
-                    "Discord rate limit exceeded. Please reduce posting frequency."
+                    "Discord rate limit exceeded after {} attempts, next request allowed in {:.1}s",
+                    rate_limited,
+                    retry_after.as_secs_f64()
                 ));
             }
+        };
+
+        if response.status().is_success() {
+            let result: Value = response.json().await.unwrap_or_default();
+            match result["id"].as_str() {
+                Some(id) => Ok(format!("Published to Discord via webhook: {}", id)),
+                None => Ok("Published to Discord via webhook".to_string()),
+            }
+        } else {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
 
             // Handle 400 Bad Request (invalid webhook)
             if status.as_u16() == 400 {
@@ -121,7 +372,7 @@ impl Publisher for DiscordPublisher {
     }
 
     fn get_type(&self) -> &'static str {
-        "Discord"
+        "discord"
     }
 
     fn get_id(&self) -> &str {
@@ -132,3 +383,187 @@ impl Publisher for DiscordPublisher {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    #[test]
+    fn test_webhook_url_hosts() {
+        for url in [
+            "https://discord.com/api/webhooks/123/token",
+            "https://discordapp.com/api/webhooks/123/token",
+            "https://canary.discord.com/api/webhooks/123/token",
+            "https://discord.com/api/v10/webhooks/123/token",
+        ] {
+            assert!(validate_webhook_url(url).is_ok(), "{}", url);
+        }
+        for url in [
+            "",
+            "http://discord.com/api/webhooks/123/token",
+            "https://discord.com.evil.net/api/webhooks/123/token",
+            "https://discord.com/api/webhooks/123",
+        ] {
+            assert!(validate_webhook_url(url).is_err(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Retry-After", "2".parse().unwrap());
+        headers.insert("X-RateLimit-Remaining", "0".parse().unwrap());
+        headers.insert("X-RateLimit-Reset-After", "0.5".parse().unwrap());
+
+        assert_eq!(
+            retry_after(&headers, &json!({ "retry_after": 1.25 })),
+            Duration::from_millis(1250)
+        );
+        assert_eq!(retry_after(&headers, &Value::Null), Duration::from_secs(2));
+        assert_eq!(
+            bucket_reset_after(&headers),
+            Some(Duration::from_millis(500))
+        );
+
+        headers.insert("X-RateLimit-Remaining", "3".parse().unwrap());
+        assert_eq!(bucket_reset_after(&headers), None);
+        assert_eq!(
+            retry_after(&HeaderMap::new(), &Value::Null),
+            DEFAULT_RETRY_AFTER
+        );
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#5865F2").unwrap(), 0x5865F2);
+        assert_eq!(parse_color("16711680").unwrap(), 0xFF0000);
+        assert!(parse_color("#FFF").is_err());
+        assert!(parse_color("red").is_err());
+    }
+
+    /// Serve canned responses in order, counting the requests, and return a
+    /// publisher whose webhook points at the server
+    async fn publisher_with_responses(
+        responses: Vec<String>,
+    ) -> (DiscordPublisher, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let webhook_url = format!(
+            "http://{}/api/webhooks/123/token",
+            listener.local_addr().unwrap()
+        );
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                read_request(&mut stream).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let publisher = DiscordPublisher {
+            id: "discord".to_string(),
+            webhook_url,
+            template: "{{ title }}".to_string(),
+            embed: false,
+            embed_description_template: String::new(),
+            embed_image: DiscordImage::None,
+            color: None,
+            username: None,
+            avatar_url: None,
+            thread_id: None,
+            thread_name: None,
+            client: Client::new(),
+            renderer: TemplateRenderer::new(),
+        };
+        (publisher, requests)
+    }
+
+    /// Read the request headers and the JSON body
+    async fn read_request(stream: &mut TcpStream) {
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 4096];
+        loop {
+            let read = stream.read(&mut buffer).await.unwrap();
+            request.extend_from_slice(&buffer[..read]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end]
+                    .lines()
+                    .find_map(|line| {
+                        line.to_ascii_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                if request.len() >= end + 4 + length {
+                    return;
+                }
+            }
+            if read == 0 {
+                return;
+            }
+        }
+    }
+
+    fn response(status: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_publish() {
+        let post = Post::for_test("feed", "guid", chrono::Utc::now());
+        let too_many = |seconds: f64| {
+            response(
+                "429 Too Many Requests",
+                &json!({ "retry_after": seconds }).to_string(),
+            )
+        };
+
+        // Short waits are retried until the message goes through
+        let (publisher, requests) = publisher_with_responses(vec![
+            too_many(0.05),
+            too_many(0.05),
+            response("200 OK", r#"{"id": "42"}"#),
+        ])
+        .await;
+        let result = publisher.publish(&post).await.unwrap();
+        assert!(result.contains("42"), "{}", result);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // A wait beyond the budget fails right after the 429
+        let (publisher, requests) = publisher_with_responses(vec![too_many(60.0)]).await;
+        let error = publisher.publish(&post).await.err().unwrap();
+        assert!(
+            error.to_string().contains("rate limit exceeded"),
+            "{}",
+            error
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Endless 429s with no wait stop after a fixed number of attempts
+        let responses = vec![too_many(0.0); MAX_RATE_LIMIT_ATTEMPTS as usize + 1];
+        let (publisher, requests) = publisher_with_responses(responses).await;
+        let error = publisher.publish(&post).await.err().unwrap();
+        assert!(
+            error.to_string().contains("rate limit exceeded"),
+            "{}",
+            error
+        );
+        assert_eq!(
+            requests.load(Ordering::SeqCst),
+            MAX_RATE_LIMIT_ATTEMPTS as usize
+        );
+    }
+}
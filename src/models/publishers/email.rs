@@ -0,0 +1,251 @@
+use super::Publisher;
+use crate::models::{
+    strip_html, OutputEscape, Post, SmtpSecurity, TemplateContext, TemplateRenderer,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+pub struct EmailPublisher {
+    #[allow(dead_code)]
+    pub id: String,
+    pub from: Mailbox,
+    pub to: Vec<Mailbox>,
+    pub subject_template: String,
+    pub template: String,
+    pub text_template: Option<String>,
+    mailer: AsyncSmtpTransport<Tokio1Executor>,
+    renderer: TemplateRenderer,
+    html_renderer: TemplateRenderer,
+}
+
+impl EmailPublisher {
+    #[expect(clippy::too_many_arguments)]
+    pub fn new(
+        id: String,
+        smtp_host: &str,
+        smtp_port: Option<u16>,
+        security: SmtpSecurity,
+        username: Option<String>,
+        password: Option<String>,
+        from: &str,
+        to: &[String],
+        subject_template: String,
+        template: String,
+        text_template: Option<String>,
+    ) -> Result<Self> {
+        let from = from
+            .parse::<Mailbox>()
+            .map_err(|e| anyhow::anyhow!("Invalid from address '{}': {}", from, e))?;
+        let to = to
+            .iter()
+            .map(|address| {
+                address
+                    .parse::<Mailbox>()
+                    .map_err(|e| anyhow::anyhow!("Invalid to address '{}': {}", address, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if to.is_empty() {
+            return Err(anyhow::anyhow!("Email publisher {} has no recipients", id));
+        }
+
+        let builder = match security {
+            SmtpSecurity::Starttls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(smtp_host)?
+                    .port(smtp_port.unwrap_or(587))
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(smtp_host)?
+                .port(smtp_port.unwrap_or(465)),
+            SmtpSecurity::Plain => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(smtp_host)
+                    .port(smtp_port.unwrap_or(25))
+            }
+        };
+
+        let mailer = match (username, password) {
+            (Some(username), Some(password)) => builder
+                .credentials(Credentials::new(username, password))
+                .build(),
+            (None, None) => builder.build(),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Email publisher {} needs both username and password",
+                    id
+                ))
+            }
+        };
+
+        Ok(Self {
+            id,
+            from,
+            to,
+            subject_template,
+            template,
+            text_template,
+            mailer,
+            renderer: TemplateRenderer::new(),
+            html_renderer: TemplateRenderer::with_escape(OutputEscape::Html),
+        })
+    }
+}
+
+/// Turns the entities left by escaping back into characters for the
+/// plain-text alternative
+fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[async_trait]
+impl Publisher for EmailPublisher {
+    async fn publish(&self, post: &Post) -> Result<String> {
+        let context = TemplateContext::from_post(post, &self.id);
+
+        // Subjects are a single line
+        let subject = self
+            .renderer
+            .render(&self.subject_template, &context)?
+            .lines()
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join(" ");
+        // The HTML body escapes every value; fields that hold HTML use `| safe`
+        let html_body = self.html_renderer.render(&self.template, &context)?;
+        let text_body = match &self.text_template {
+            Some(text_template) => self.renderer.render(text_template, &context)?,
+            None => unescape_html(&strip_html(&html_body)),
+        };
+
+        let mut builder = Message::builder().from(self.from.clone()).subject(subject);
+        for recipient in &self.to {
+            builder = builder.to(recipient.clone());
+        }
+        let message = builder.multipart(MultiPart::alternative_plain_html(text_body, html_body))?;
+
+        let response = self
+            .mailer
+            .send(message)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to send email: {}", e))?;
+
+        Ok(format!(
+            "Published to email: {} recipient(s), {}",
+            self.to.len(),
+            response.code()
+        ))
+    }
+
+    fn get_type(&self) -> &'static str {
+        "email"
+    }
+
+    fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// Accepts one SMTP session and returns the DATA it received
+    async fn smtp_stand_in(listener: TcpListener) -> String {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+
+        let mut data = String::new();
+        let mut in_data = false;
+        while let Some(line) = lines.next_line().await.unwrap() {
+            if in_data {
+                if line == "." {
+                    in_data = false;
+                    writer.write_all(b"250 OK queued\r\n").await.unwrap();
+                } else {
+                    data.push_str(&line);
+                    data.push('\n');
+                }
+                continue;
+            }
+            let reply: &[u8] = match line.get(..4).unwrap_or_default() {
+                "EHLO" | "HELO" => b"250 localhost\r\n",
+                "DATA" => {
+                    in_data = true;
+                    b"354 End data with <CR><LF>.<CR><LF>\r\n"
+                }
+                "QUIT" => {
+                    writer.write_all(b"221 Bye\r\n").await.unwrap();
+                    break;
+                }
+                _ => b"250 OK\r\n",
+            };
+            writer.write_all(reply).await.unwrap();
+        }
+        data
+    }
+
+    #[tokio::test]
+    async fn test_publish_sends_multipart_email() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(smtp_stand_in(listener));
+
+        let publisher = EmailPublisher::new(
+            "email".to_string(),
+            "127.0.0.1",
+            Some(port),
+            SmtpSecurity::Plain,
+            None,
+            None,
+            "Populatrs <populatrs@example.com>",
+            &["list@example.com".to_string()],
+            "[Blog] {{ title }}".to_string(),
+            TemplateRenderer::get_default_template("email"),
+            None,
+        )
+        .unwrap();
+
+        let mut post = Post::for_test("feed", "guid", Utc::now());
+        post.title = "Rust & <Go>".to_string();
+        post.link = "https://example.com/post".to_string();
+        post.description = Some("<p>Hello <b>world</b></p>".to_string());
+
+        let result = publisher.publish(&post).await.unwrap();
+        assert!(result.contains("1 recipient(s)"), "{}", result);
+
+        let data = server.await.unwrap();
+        assert!(data.contains("Subject: [Blog] Rust & <Go>"), "{}", data);
+        assert!(data.contains("multipart/alternative"), "{}", data);
+        assert!(data.contains("Content-Type: text/plain"), "{}", data);
+        assert!(data.contains("Content-Type: text/html"), "{}", data);
+        // The body is quoted-printable, so `=` shows up as `=3D`. The title is
+        // escaped in the HTML part and the description is kept as HTML
+        assert!(
+            data.contains(
+                "<h2><a href=3D\"https://example.com/post\">Rust &amp; &lt;Go&gt;</a></h2>"
+            ),
+            "{}",
+            data
+        );
+        assert!(
+            data.contains("<div><p>Hello <b>world</b></p></div>"),
+            "{}",
+            data
+        );
+        // The plain-text alternative has neither tags nor entities
+        assert!(data.contains("\nRust & <Go>\nHello world\n"), "{}", data);
+    }
+}
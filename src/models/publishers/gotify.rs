@@ -0,0 +1,120 @@
+use super::Publisher;
+use crate::models::{Post, TemplateContext, TemplateRenderer};
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::json;
+
+pub struct GotifyPublisher {
+    #[allow(dead_code)]
+    pub id: String,
+    pub server_url: String,
+    pub app_token: String,
+    pub priority: Option<u8>,
+    pub markdown: bool,
+    pub title_template: String,
+    pub template: String,
+    client: Client,
+    renderer: TemplateRenderer,
+}
+
+impl GotifyPublisher {
+    pub fn new(
+        id: String,
+        server_url: String,
+        app_token: String,
+        priority: Option<u8>,
+        markdown: bool,
+        title_template: String,
+        template: String,
+    ) -> Result<Self> {
+        if let Some(priority) = priority {
+            if priority > 10 {
+                return Err(anyhow::anyhow!(
+                    "Invalid Gotify priority {}: expected 0 to 10",
+                    priority
+                ));
+            }
+        }
+
+        Ok(Self {
+            id,
+            server_url,
+            app_token,
+            priority,
+            markdown,
+            title_template,
+            template,
+            client: Client::new(),
+            renderer: TemplateRenderer::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl Publisher for GotifyPublisher {
+    async fn publish(&self, post: &Post) -> Result<String> {
+        let context = TemplateContext::from_post(post, &self.id);
+
+        let title = self.renderer.render(&self.title_template, &context)?;
+        let message = self.renderer.render(&self.template, &context)?;
+
+        let mut extras = json!({
+            "client::notification": {
+                "click": { "url": post.link }
+            }
+        });
+        if self.markdown {
+            extras["client::display"] = json!({ "contentType": "text/markdown" });
+        }
+
+        let mut payload = json!({
+            "title": title,
+            "message": message,
+            "extras": extras
+        });
+        if let Some(priority) = self.priority {
+            payload["priority"] = json!(priority);
+        }
+
+        let url = format!("{}/message", self.server_url.trim_end_matches('/'));
+        let response = self
+            .client
+            .post(&url)
+            .header("X-Gotify-Key", &self.app_token)
+            .json(&payload)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            let result: serde_json::Value = response.json().await?;
+            Ok(format!(
+                "Published to Gotify: {}",
+                result["id"]
+                    .as_u64()
+                    .map(|id| id.to_string())
+                    .unwrap_or_else(|| "unknown".to_string())
+            ))
+        } else {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            Err(anyhow::anyhow!(
+                "Failed to publish to Gotify: {} - {}",
+                status,
+                error_text
+            ))
+        }
+    }
+
+    fn get_type(&self) -> &'static str {
+        "gotify"
+    }
+
+    fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
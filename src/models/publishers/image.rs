@@ -0,0 +1,128 @@
+use anyhow::Result;
+use reqwest::Client;
+
+/// An image downloaded to be uploaded to a platform
+pub struct DownloadedImage {
+    pub bytes: Vec<u8>,
+    pub content_type: String,
+}
+
+/// Download an image, rejecting non-image content types and anything larger
+/// than `max_bytes`. The declared `Content-Length` is checked first and the
+/// body is read in chunks, so an oversized image is never fully buffered.
+pub async fn download_image(
+    client: &Client,
+    image_url: &str,
+    max_bytes: usize,
+) -> Result<DownloadedImage> {
+    let mut response = client.get(image_url).send().await?;
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!(
+            "Failed to download image {}: {}",
+            image_url,
+            response.status()
+        ));
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    if !content_type.starts_with("image/") {
+        return Err(anyhow::anyhow!(
+            "Image URL {} returned non-image content type '{}'",
+            image_url,
+            content_type
+        ));
+    }
+
+    let too_large = |size: u64| {
+        anyhow::anyhow!(
+            "Image {} is too large: {} bytes or more (max {})",
+            image_url,
+            size,
+            max_bytes
+        )
+    };
+    if let Some(length) = response.content_length() {
+        if length > max_bytes as u64 {
+            return Err(too_large(length));
+        }
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() > max_bytes {
+            return Err(too_large(bytes.len() as u64));
+        }
+    }
+
+    Ok(DownloadedImage {
+        bytes,
+        content_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve each canned HTTP response once, in order, and return the base URL
+    async fn serve(responses: Vec<Vec<u8>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream.write_all(&response).await;
+            }
+        });
+        url
+    }
+
+    fn response(content_type: &str, length: Option<usize>, body: &[u8]) -> Vec<u8> {
+        let mut head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nConnection: close\r\n",
+            content_type
+        );
+        if let Some(length) = length {
+            head.push_str(&format!("Content-Length: {}\r\n", length));
+        }
+        head.push_str("\r\n");
+        [head.into_bytes(), body.to_vec()].concat()
+    }
+
+    #[tokio::test]
+    async fn test_download_image_limits() {
+        let client = Client::new();
+        let url = serve(vec![
+            response("image/png", Some(4), b"\x89PNG"),
+            response("text/html", Some(6), b"<html>"),
+            // A declared length over the limit is rejected without reading the body
+            response("image/png", Some(10_000), b""),
+            // Without Content-Length the body is counted while it is read
+            response("image/png", None, &[0u8; 64]),
+        ])
+        .await;
+
+        let image = download_image(&client, &url, 16).await.unwrap();
+        assert_eq!(image.bytes, b"\x89PNG");
+        assert_eq!(image.content_type, "image/png");
+
+        let error = download_image(&client, &url, 16).await.err().unwrap();
+        assert!(error.to_string().contains("non-image"), "{}", error);
+
+        let error = download_image(&client, &url, 16).await.err().unwrap();
+        assert!(error.to_string().contains("10000 bytes"), "{}", error);
+
+        let error = download_image(&client, &url, 16).await.err().unwrap();
+        assert!(error.to_string().contains("too large"), "{}", error);
+    }
+}
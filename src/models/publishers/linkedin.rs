@@ -301,11 +301,7 @@ impl Publisher for LinkedInPublisher {
     async fn publish(&self, post: &Post) -> Result<String> {
         let url = "https://api.linkedin.com/v2/ugcPosts";
 
-        let context = TemplateContext {
-            title: post.title.clone(),
-            description: post.description.clone().unwrap_or_default(),
-            url: post.link.clone(),
-        };
+        let context = TemplateContext::from_post(post, &self.id);
 
         let commentary = self.renderer.render(&self.template, &context)?;
 
@@ -1,20 +1,23 @@
 use super::{
-    BlueskyPublisher, DiscordPublisher, LinkedInPublisher, MastodonPublisher, MatrixPublisher,
-    OpenObservePublisher, Publisher, TelegramPublisher, ThreadsPublisher, XPublisher,
+    BlueskyPublisher, DiscordPublisher, EmailPublisher, GotifyPublisher, LinkedInPublisher,
+    MastodonPublisher, MatrixPublisher, NtfyPublisher, OpenObservePublisher, Publisher,
+    SlackPublisher, TelegramPublisher, ThreadsPublisher, WebhookPublisher, XPublisher,
 };
-use crate::models::{Post, PublisherConfig, TemplateRenderer};
+use crate::metrics;
+use crate::models::{OutputEscape, Post, PublisherConfig, TemplateRenderer};
 use anyhow::Result;
 use std::collections::HashMap;
 
 #[allow(dead_code)]
 pub fn create_publisher(id: String, config: &PublisherConfig) -> Result<Box<dyn Publisher>> {
-    create_publisher_with_config_path(id, config, None)
+    create_publisher_with_config_path(id, config, None, None)
 }
 
 pub fn create_publisher_with_config_path(
     id: String,
     config: &PublisherConfig,
     config_path: Option<String>,
+    data_dir: Option<String>,
 ) -> Result<Box<dyn Publisher>> {
     match config {
         PublisherConfig::Telegram {
@@ -23,10 +26,17 @@ pub fn create_publisher_with_config_path(
             parse_mode,
             message_thread_id,
             template,
+            attach_image,
+            buttons,
         } => {
-            let template_str = template
-                .clone()
-                .unwrap_or_else(|| TemplateRenderer::get_default_template("telegram"));
+            let template_str = template.clone().unwrap_or_else(|| {
+                let default = match OutputEscape::for_telegram_parse_mode(parse_mode.as_deref()) {
+                    OutputEscape::Html => "telegram_html",
+                    OutputEscape::MarkdownV2 => "telegram_markdown_v2",
+                    _ => "telegram",
+                };
+                TemplateRenderer::get_default_template(default)
+            });
             Ok(Box::new(TelegramPublisher::new(
                 id,
                 bot_token.clone(),
@@ -34,6 +44,8 @@ pub fn create_publisher_with_config_path(
                 parse_mode.clone(),
                 message_thread_id.clone(),
                 template_str,
+                attach_image.unwrap_or(true),
+                buttons.clone(),
             )))
         }
         PublisherConfig::X {
@@ -62,15 +74,22 @@ pub fn create_publisher_with_config_path(
             server_url,
             access_token,
             template,
+            attach_image,
+            alt_text_template,
         } => {
             let template_str = template
                 .clone()
                 .unwrap_or_else(|| TemplateRenderer::get_default_template("mastodon"));
+            let alt_text_str = alt_text_template
+                .clone()
+                .unwrap_or_else(|| TemplateRenderer::get_default_template("mastodon_alt_text"));
             Ok(Box::new(MastodonPublisher::new(
                 id,
                 server_url.clone(),
                 access_token.clone(),
                 template_str,
+                attach_image.unwrap_or(true),
+                alt_text_str,
             )))
         }
         PublisherConfig::LinkedIn {
@@ -138,6 +157,7 @@ pub fn create_publisher_with_config_path(
             password,
             pds_url,
             template,
+            link_card,
         } => {
             let template_str = template
                 .clone()
@@ -148,6 +168,8 @@ pub fn create_publisher_with_config_path(
                 password.clone(),
                 pds_url.clone(),
                 template_str,
+                link_card.unwrap_or(true),
+                data_dir,
             )))
         }
         PublisherConfig::Threads {
@@ -168,15 +190,161 @@ pub fn create_publisher_with_config_path(
         PublisherConfig::Discord {
             webhook_url,
             template,
+            embed,
+            embed_description_template,
+            embed_image,
+            color,
+            username,
+            avatar_url,
+            thread_id,
+            thread_name,
+        } => {
+            let embed = embed.unwrap_or(false);
+            let template_str = template.clone().unwrap_or_else(|| {
+                TemplateRenderer::get_default_template(if embed {
+                    "discord_embed"
+                } else {
+                    "discord"
+                })
+            });
+            let embed_description_str = embed_description_template.clone().unwrap_or_else(|| {
+                TemplateRenderer::get_default_template("discord_embed_description")
+            });
+            Ok(Box::new(DiscordPublisher::new(
+                id,
+                webhook_url.clone(),
+                template_str,
+                embed,
+                embed_description_str,
+                *embed_image,
+                color.as_deref(),
+                username.clone(),
+                avatar_url.clone(),
+                thread_id.clone(),
+                thread_name.clone(),
+            )?))
+        }
+        PublisherConfig::Slack {
+            webhook_url,
+            bot_token,
+            channel,
+            thread_ts,
+            template,
         } => {
             let template_str = template
                 .clone()
-                .unwrap_or_else(|| TemplateRenderer::get_default_template("discord"));
-            Ok(Box::new(DiscordPublisher::new(
+                .unwrap_or_else(|| TemplateRenderer::get_default_template("slack"));
+            Ok(Box::new(SlackPublisher::new(
                 id,
                 webhook_url.clone(),
+                bot_token.clone(),
+                channel.clone(),
+                thread_ts.clone(),
                 template_str,
-            )))
+            )?))
+        }
+        PublisherConfig::Email {
+            smtp_host,
+            smtp_port,
+            security,
+            username,
+            password,
+            from,
+            to,
+            subject_template,
+            template,
+            text_template,
+        } => {
+            let subject_str = subject_template
+                .clone()
+                .unwrap_or_else(|| TemplateRenderer::get_default_template("email_subject"));
+            let template_str = template
+                .clone()
+                .unwrap_or_else(|| TemplateRenderer::get_default_template("email"));
+            Ok(Box::new(EmailPublisher::new(
+                id,
+                smtp_host,
+                *smtp_port,
+                *security,
+                username.clone(),
+                password.clone(),
+                from,
+                to,
+                subject_str,
+                template_str,
+                text_template.clone(),
+            )?))
+        }
+        PublisherConfig::Ntfy {
+            topic_url,
+            access_token,
+            username,
+            password,
+            title_template,
+            tags,
+            priority,
+            template,
+        } => {
+            let title_str = title_template
+                .clone()
+                .unwrap_or_else(|| TemplateRenderer::get_default_template("ntfy_title"));
+            let template_str = template
+                .clone()
+                .unwrap_or_else(|| TemplateRenderer::get_default_template("ntfy"));
+            Ok(Box::new(NtfyPublisher::new(
+                id,
+                topic_url,
+                access_token.clone(),
+                username.clone(),
+                password.clone(),
+                title_str,
+                tags.clone().unwrap_or_default(),
+                *priority,
+                template_str,
+            )?))
+        }
+        PublisherConfig::Gotify {
+            server_url,
+            app_token,
+            priority,
+            markdown,
+            title_template,
+            template,
+        } => {
+            let title_str = title_template
+                .clone()
+                .unwrap_or_else(|| TemplateRenderer::get_default_template("gotify_title"));
+            let template_str = template
+                .clone()
+                .unwrap_or_else(|| TemplateRenderer::get_default_template("gotify"));
+            Ok(Box::new(GotifyPublisher::new(
+                id,
+                server_url.clone(),
+                app_token.clone(),
+                *priority,
+                markdown.unwrap_or(true),
+                title_str,
+                template_str,
+            )?))
+        }
+        PublisherConfig::Webhook {
+            url,
+            method,
+            headers,
+            template,
+            success_status_codes,
+        } => {
+            let template_str = template
+                .clone()
+                .unwrap_or_else(|| TemplateRenderer::get_default_template("webhook"));
+            Ok(Box::new(WebhookPublisher::new(
+                id,
+                url.clone(),
+                method.clone(),
+                headers.clone(),
+                template_str,
+                success_status_codes.clone(),
+            )?))
         }
     }
 }
@@ -184,6 +352,7 @@ pub fn create_publisher_with_config_path(
 pub struct PublisherManager {
     publishers: HashMap<String, Box<dyn Publisher>>,
     config_path: Option<String>,
+    data_dir: Option<String>,
 }
 
 impl PublisherManager {
@@ -192,6 +361,7 @@ impl PublisherManager {
         Self {
             publishers: HashMap::new(),
             config_path: None,
+            data_dir: None,
         }
     }
 
@@ -199,12 +369,23 @@ impl PublisherManager {
         Self {
             publishers: HashMap::new(),
             config_path: Some(config_path),
+            data_dir: None,
         }
     }
 
+    /// Directory where publishers keep state such as session tokens
+    pub fn with_data_dir(mut self, data_dir: String) -> Self {
+        self.data_dir = Some(data_dir);
+        self
+    }
+
     pub fn add_publisher(&mut self, id: String, config: &PublisherConfig) -> Result<()> {
-        let publisher =
-            create_publisher_with_config_path(id.clone(), config, self.config_path.clone())?;
+        let publisher = create_publisher_with_config_path(
+            id.clone(),
+            config,
+            self.config_path.clone(),
+            self.data_dir.clone(),
+        )?;
         self.publishers.insert(id, publisher);
         Ok(())
     }
@@ -217,17 +398,33 @@ impl PublisherManager {
         let mut results = Vec::new();
 
         for id in publisher_ids {
-            if let Some(publisher) = self.publishers.get(id) {
-                let result = publisher.publish(post).await;
-                results.push(result);
-            } else {
-                results.push(Err(anyhow::anyhow!("Publisher not found: {}", id)));
-            }
+            results.push(self.publish_to(post, id).await);
         }
 
         results
     }
 
+    pub async fn publish_to(&self, post: &Post, publisher_id: &str) -> Result<String> {
+        let publisher = self
+            .publishers
+            .get(publisher_id)
+            .ok_or_else(|| anyhow::anyhow!("Publisher not found: {}", publisher_id))?;
+
+        let result = publisher.publish(post).await;
+        metrics::PUBLISHES
+            .with_label_values(&[
+                publisher.get_type(),
+                publisher_id,
+                metrics::result_label(result.is_ok()),
+            ])
+            .inc();
+        result
+    }
+
+    pub fn has_publisher(&self, id: &str) -> bool {
+        self.publishers.contains_key(id)
+    }
+
     #[allow(dead_code)]
     pub fn get_publisher(&self, id: &str) -> Option<&dyn Publisher> {
         self.publishers.get(id).map(|p| p.as_ref())
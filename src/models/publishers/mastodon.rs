@@ -1,9 +1,19 @@
+use super::image::download_image;
 use super::Publisher;
-use crate::models::{Post, TemplateContext, TemplateRenderer};
+use crate::models::{LengthBudget, Post, TemplateContext, TemplateRenderer};
 use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::multipart::{Form, Part};
+use reqwest::{Client, StatusCode};
 use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Mastodon's default upload limit for images
+const MAX_IMAGE_BYTES: usize = 16 * 1024 * 1024;
+/// Mastodon's limit for media descriptions
+const MAX_ALT_TEXT_CHARS: usize = 1500;
+const MEDIA_POLL_ATTEMPTS: u32 = 10;
+const MEDIA_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 pub struct MastodonPublisher {
     #[allow(dead_code)]
@@ -11,21 +21,117 @@ pub struct MastodonPublisher {
     pub server_url: String,
     pub access_token: String,
     pub template: String,
+    pub attach_image: bool,
+    pub alt_text_template: String,
     client: Client,
     renderer: TemplateRenderer,
 }
 
 impl MastodonPublisher {
-    pub fn new(id: String, server_url: String, access_token: String, template: String) -> Self {
+    pub fn new(
+        id: String,
+        server_url: String,
+        access_token: String,
+        template: String,
+        attach_image: bool,
+        alt_text_template: String,
+    ) -> Self {
         Self {
             id,
             server_url,
             access_token,
             template,
+            attach_image,
+            alt_text_template,
             client: Client::new(),
             renderer: TemplateRenderer::new(),
         }
     }
+
+    /// Download the image and upload it to Mastodon, returning the media id
+    /// once the server has finished processing it
+    async fn upload_image(&self, image_url: &str, alt_text: &str) -> Result<String> {
+        let image = download_image(&self.client, image_url, MAX_IMAGE_BYTES).await?;
+
+        let file_name = image_url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit('/').next())
+            .filter(|name| !name.is_empty())
+            .unwrap_or("image")
+            .to_string();
+
+        let part = Part::bytes(image.bytes)
+            .file_name(file_name)
+            .mime_str(&image.content_type)?;
+        let form = Form::new()
+            .part("file", part)
+            .text("description", alt_text.to_string());
+
+        let response = self
+            .client
+            .post(format!("{}/api/v2/media", self.server_url))
+            .bearer_auth(&self.access_token)
+            .multipart(form)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "Failed to upload media to Mastodon: {} - {}",
+                status,
+                error_text
+            ));
+        }
+
+        let media: Value = response.json().await?;
+        let media_id = media["id"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Mastodon media response has no id"))?
+            .to_string();
+
+        // 202 means the server is still processing the file
+        if status == StatusCode::ACCEPTED {
+            self.wait_for_media(&media_id).await?;
+        }
+
+        Ok(media_id)
+    }
+
+    async fn wait_for_media(&self, media_id: &str) -> Result<()> {
+        let url = format!("{}/api/v1/media/{}", self.server_url, media_id);
+
+        for _ in 0..MEDIA_POLL_ATTEMPTS {
+            tokio::time::sleep(MEDIA_POLL_INTERVAL).await;
+
+            let response = self
+                .client
+                .get(&url)
+                .bearer_auth(&self.access_token)
+                .send()
+                .await?;
+
+            match response.status() {
+                StatusCode::OK => return Ok(()),
+                StatusCode::PARTIAL_CONTENT => continue,
+                status => {
+                    return Err(anyhow::anyhow!(
+                        "Failed to check Mastodon media {}: {}",
+                        media_id,
+                        status
+                    ))
+                }
+            }
+        }
+
+        Err(anyhow::anyhow!(
+            "Mastodon media {} still processing after {} attempts",
+            media_id,
+            MEDIA_POLL_ATTEMPTS
+        ))
+    }
 }
 
 #[async_trait]
@@ -33,19 +139,30 @@ impl Publisher for MastodonPublisher {
     async fn publish(&self, post: &Post) -> Result<String> {
         let url = format!("{}/api/v1/statuses", self.server_url);
 
-        let context = TemplateContext {
-            title: post.title.clone(),
-            description: post.description.clone().unwrap_or_default(),
-            url: post.link.clone(),
-        };
+        let context = TemplateContext::from_post(post, &self.id);
 
-        let status = self.renderer.render(&self.template, &context)?;
+        let status = LengthBudget::MASTODON.render(&self.renderer, &self.template, &context)?;
 
-        let payload = json!({
+        let mut payload = json!({
             "status": status,
             "visibility": "public"
         });
 
+        // A missing image should not block the status itself
+        if let Some(image_url) = post.image_url.as_deref().filter(|_| self.attach_image) {
+            let alt_text = self
+                .renderer
+                .render(&self.alt_text_template, &context)?
+                .chars()
+                .take(MAX_ALT_TEXT_CHARS)
+                .collect::<String>();
+
+            match self.upload_image(image_url, &alt_text).await {
+                Ok(media_id) => payload["media_ids"] = json!([media_id]),
+                Err(e) => log::warn!("Posting to Mastodon without image: {}", e),
+            }
+        }
+
         let response = self
             .client
             .post(&url)
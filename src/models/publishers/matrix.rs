@@ -45,11 +45,7 @@ impl Publisher for MatrixPublisher {
             self.homeserver_url, self.room_id, txn_id
         );
 
-        let context = TemplateContext {
-            title: post.title.clone(),
-            description: post.description.clone().unwrap_or_default(),
-            url: post.link.clone(),
-        };
+        let context = TemplateContext::from_post(post, &self.id);
 
         let message = self.renderer.render(&self.template, &context)?;
 
@@ -6,7 +6,6 @@ use std::any::Any;
 #[async_trait]
 pub trait Publisher: Send + Sync {
     async fn publish(&self, post: &Post) -> Result<String>;
-    #[allow(dead_code)]
     fn get_type(&self) -> &'static str;
     #[allow(dead_code)]
     fn get_id(&self) -> &str;
@@ -15,22 +14,33 @@ pub trait Publisher: Send + Sync {
 
 pub mod bluesky;
 pub mod discord;
+pub mod email;
+pub mod gotify;
+mod image;
 pub mod linkedin;
 pub mod manager;
 pub mod mastodon;
 pub mod matrix;
+pub mod ntfy;
 pub mod openobserve;
+pub mod slack;
 pub mod telegram;
 pub mod threads;
+pub mod webhook;
 pub mod x;
 
 pub use bluesky::BlueskyPublisher;
 pub use discord::DiscordPublisher;
+pub use email::EmailPublisher;
+pub use gotify::GotifyPublisher;
 pub use linkedin::LinkedInPublisher;
 pub use manager::PublisherManager;
 pub use mastodon::MastodonPublisher;
 pub use matrix::MatrixPublisher;
+pub use ntfy::NtfyPublisher;
 pub use openobserve::OpenObservePublisher;
+pub use slack::SlackPublisher;
 pub use telegram::TelegramPublisher;
 pub use threads::ThreadsPublisher;
+pub use webhook::WebhookPublisher;
 pub use x::XPublisher;
@@ -0,0 +1,142 @@
+use super::Publisher;
+use crate::models::{Post, TemplateContext, TemplateRenderer};
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::json;
+use url::Url;
+
+pub struct NtfyPublisher {
+    #[allow(dead_code)]
+    pub id: String,
+    pub server_url: String,
+    pub topic: String,
+    pub access_token: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub title_template: String,
+    pub tags: Vec<String>,
+    pub priority: Option<u8>,
+    pub template: String,
+    client: Client,
+    renderer: TemplateRenderer,
+}
+
+impl NtfyPublisher {
+    #[expect(clippy::too_many_arguments)]
+    pub fn new(
+        id: String,
+        topic_url: &str,
+        access_token: Option<String>,
+        username: Option<String>,
+        password: Option<String>,
+        title_template: String,
+        tags: Vec<String>,
+        priority: Option<u8>,
+        template: String,
+    ) -> Result<Self> {
+        if let Some(priority) = priority {
+            if !(1..=5).contains(&priority) {
+                return Err(anyhow::anyhow!(
+                    "Invalid ntfy priority {}: expected 1 to 5",
+                    priority
+                ));
+            }
+        }
+        if username.is_some() != password.is_some() {
+            return Err(anyhow::anyhow!(
+                "ntfy publisher {} needs both username and password",
+                id
+            ));
+        }
+
+        // Messages are published as JSON to the server root, so split
+        // https://ntfy.sh/my-topic into server and topic
+        let mut url = Url::parse(topic_url)
+            .map_err(|e| anyhow::anyhow!("Invalid ntfy topic URL '{}': {}", topic_url, e))?;
+        let topic = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|topic| !topic.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("ntfy topic URL has no topic: {}", topic_url))?
+            .to_string();
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid ntfy topic URL: {}", topic_url))?
+            .pop();
+
+        Ok(Self {
+            id,
+            server_url: url.to_string(),
+            topic,
+            access_token,
+            username,
+            password,
+            title_template,
+            tags,
+            priority,
+            template,
+            client: Client::new(),
+            renderer: TemplateRenderer::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl Publisher for NtfyPublisher {
+    async fn publish(&self, post: &Post) -> Result<String> {
+        let context = TemplateContext::from_post(post, &self.id);
+
+        let title = self.renderer.render(&self.title_template, &context)?;
+        let message = self.renderer.render(&self.template, &context)?;
+
+        let mut payload = json!({
+            "topic": self.topic,
+            "title": title,
+            "message": message,
+            "click": post.link
+        });
+        if !self.tags.is_empty() {
+            payload["tags"] = json!(self.tags);
+        }
+        if let Some(priority) = self.priority {
+            payload["priority"] = json!(priority);
+        }
+
+        let mut request = self.client.post(&self.server_url).json(&payload);
+        if let Some(token) = &self.access_token {
+            request = request.bearer_auth(token);
+        } else if let Some(username) = &self.username {
+            request = request.basic_auth(username, self.password.as_ref());
+        }
+
+        let response = request.send().await?;
+
+        if response.status().is_success() {
+            let result: serde_json::Value = response.json().await?;
+            Ok(format!(
+                "Published to ntfy: {}",
+                result["id"].as_str().unwrap_or("unknown")
+            ))
+        } else {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            Err(anyhow::anyhow!(
+                "Failed to publish to ntfy: {} - {}",
+                status,
+                error_text
+            ))
+        }
+    }
+
+    fn get_type(&self) -> &'static str {
+        "ntfy"
+    }
+
+    fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
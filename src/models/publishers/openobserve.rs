@@ -47,11 +47,7 @@ impl Publisher for OpenObservePublisher {
             self.url, self.organization, self.stream_name
         );
 
-        let context = TemplateContext {
-            title: post.title.clone(),
-            description: post.description.clone().unwrap_or_default(),
-            url: post.link.clone(),
-        };
+        let context = TemplateContext::from_post(post, &self.id);
 
         let formatted_message = self.renderer.render(&self.template, &context)?;
 
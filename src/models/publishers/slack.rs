@@ -0,0 +1,279 @@
+use super::Publisher;
+use crate::models::{escape_slack, OutputEscape, Post, TemplateContext, TemplateRenderer};
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+pub struct SlackPublisher {
+    #[allow(dead_code)]
+    pub id: String,
+    pub webhook_url: Option<String>,
+    pub bot_token: Option<String>,
+    pub channel: Option<String>,
+    pub thread_ts: Option<String>,
+    pub template: String,
+    client: Client,
+    renderer: TemplateRenderer,
+}
+
+impl SlackPublisher {
+    pub fn new(
+        id: String,
+        webhook_url: Option<String>,
+        bot_token: Option<String>,
+        channel: Option<String>,
+        thread_ts: Option<String>,
+        template: String,
+    ) -> Result<Self> {
+        match (&webhook_url, &bot_token, &channel) {
+            (Some(_), None, _) => {
+                if thread_ts.is_some() {
+                    log::warn!(
+                        "Slack publisher {}: thread_ts is only supported with bot_token, ignoring",
+                        id
+                    );
+                }
+            }
+            (None, Some(_), Some(_)) => {}
+            (None, Some(_), None) => {
+                return Err(anyhow::anyhow!(
+                    "Slack publisher {} uses bot_token but has no channel",
+                    id
+                ));
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Slack publisher {} needs either webhook_url or bot_token and channel",
+                    id
+                ));
+            }
+        }
+
+        Ok(Self {
+            id,
+            webhook_url,
+            bot_token,
+            channel,
+            thread_ts,
+            template,
+            client: Client::new(),
+            renderer: TemplateRenderer::with_escape(OutputEscape::Slack),
+        })
+    }
+
+    /// The template renders either plain mrkdwn text or a full JSON payload
+    /// (for example with Block Kit `blocks`). Output starting with `{` must be
+    /// a valid JSON object; values are only JSON-safe through `tojson`.
+    fn build_payload(&self, rendered: &str, post: &Post) -> Result<Value> {
+        if rendered.starts_with('{') {
+            let mut payload = match serde_json::from_str::<Value>(rendered) {
+                Ok(Value::Object(payload)) => payload,
+                Ok(_) => {
+                    return Err(anyhow::anyhow!(
+                        "Slack template output is JSON but not an object"
+                    ))
+                }
+                Err(e) => {
+                    return Err(anyhow::anyhow!(
+                        "Slack template output is not valid JSON ({}); use the tojson filter for values",
+                        e
+                    ))
+                }
+            };
+            // Slack uses `text` for notifications when only blocks are sent
+            payload
+                .entry("text")
+                .or_insert_with(|| json!(escape_slack(&post.title)));
+            return Ok(Value::Object(payload));
+        }
+
+        Ok(json!({
+            "text": rendered,
+            "mrkdwn": true
+        }))
+    }
+
+    /// Address a payload to the bot's channel and thread
+    fn message_payload(&self, mut payload: Value) -> Value {
+        payload["channel"] = json!(self.channel);
+        if let Some(thread_ts) = &self.thread_ts {
+            payload["thread_ts"] = json!(thread_ts);
+        }
+        payload
+    }
+
+    async fn post_webhook(&self, webhook_url: &str, payload: &Value) -> Result<String> {
+        let response = self.client.post(webhook_url).json(payload).send().await?;
+
+        if response.status().is_success() {
+            Ok("Published to Slack via webhook".to_string())
+        } else {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            Err(anyhow::anyhow!(
+                "Failed to publish to Slack: {} - {}",
+                status,
+                error_text
+            ))
+        }
+    }
+
+    async fn post_message(&self, bot_token: &str, payload: Value) -> Result<String> {
+        let payload = self.message_payload(payload);
+
+        let response = self
+            .client
+            .post("https://slack.com/api/chat.postMessage")
+            .bearer_auth(bot_token)
+            .json(&payload)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "Failed to publish to Slack: {} - {}",
+                status,
+                error_text
+            ));
+        }
+
+        // chat.postMessage reports errors with HTTP 200 and "ok": false
+        let result: Value = response.json().await?;
+        if result["ok"].as_bool() == Some(true) {
+            Ok(format!(
+                "Published to Slack: {}",
+                result["ts"].as_str().unwrap_or("unknown")
+            ))
+        } else {
+            Err(anyhow::anyhow!(
+                "Failed to publish to Slack: {}",
+                result["error"].as_str().unwrap_or("unknown error")
+            ))
+        }
+    }
+}
+
+#[async_trait]
+impl Publisher for SlackPublisher {
+    async fn publish(&self, post: &Post) -> Result<String> {
+        let context = TemplateContext::from_post(post, &self.id);
+
+        let rendered = self.renderer.render(&self.template, &context)?;
+        let payload = self.build_payload(&rendered, post)?;
+
+        match (&self.bot_token, &self.webhook_url) {
+            (Some(bot_token), _) => self.post_message(bot_token, payload).await,
+            (None, Some(webhook_url)) => self.post_webhook(webhook_url, &payload).await,
+            (None, None) => Err(anyhow::anyhow!("Slack publisher has no destination")),
+        }
+    }
+
+    fn get_type(&self) -> &'static str {
+        "slack"
+    }
+
+    fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn publisher(template: &str, bot: bool, thread_ts: Option<&str>) -> SlackPublisher {
+        let (webhook_url, bot_token, channel) = if bot {
+            (
+                None,
+                Some("xoxb-token".to_string()),
+                Some("C123".to_string()),
+            )
+        } else {
+            (
+                Some("https://hooks.slack.com/services/T/B/X".to_string()),
+                None,
+                None,
+            )
+        };
+        SlackPublisher::new(
+            "slack".to_string(),
+            webhook_url,
+            bot_token,
+            channel,
+            thread_ts.map(str::to_string),
+            template.to_string(),
+        )
+        .unwrap()
+    }
+
+    fn render_payload(publisher: &SlackPublisher, post: &Post) -> Result<Value> {
+        let context = TemplateContext::from_post(post, &publisher.id);
+        let rendered = publisher.renderer.render(&publisher.template, &context)?;
+        publisher.build_payload(&rendered, post)
+    }
+
+    fn post() -> Post {
+        let mut post = Post::for_test("feed", "a", Utc::now());
+        post.title = "Fish & \"chips\" <3".to_string();
+        post.image_url = Some("https://example.com/a.png".to_string());
+        post
+    }
+
+    #[test]
+    fn test_mrkdwn_payload() {
+        let slack = publisher("*<{{ url }}|{{ title }}>*", false, None);
+        let payload = render_payload(&slack, &post()).unwrap();
+        assert_eq!(
+            payload,
+            json!({
+                "text": "*<https://example.com/a|Fish &amp; \"chips\" &lt;3>*",
+                "mrkdwn": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_block_kit_payload() {
+        let template = r#"{"blocks": [{"type": "section", "text": {"type": "mrkdwn", "text": {{ ('*<' ~ url ~ '|' ~ (title | escape_slack) ~ '>*') | tojson }}}{% if image_url %}, "accessory": {"type": "image", "image_url": {{ image_url | tojson }}, "alt_text": {{ title | tojson }}}{% endif %}}]}"#;
+        let payload = render_payload(&publisher(template, false, None), &post()).unwrap();
+
+        let section = &payload["blocks"][0];
+        assert_eq!(
+            section["text"]["text"],
+            "*<https://example.com/a|Fish &amp; \"chips\" &lt;3>*"
+        );
+        assert_eq!(
+            section["accessory"]["image_url"],
+            "https://example.com/a.png"
+        );
+        assert_eq!(section["accessory"]["alt_text"], "Fish & \"chips\" <3");
+        // Notification text falls back to the escaped title
+        assert_eq!(payload["text"], "Fish &amp; \"chips\" &lt;3");
+
+        // Without tojson the quotes in the title break the JSON
+        let broken = publisher(r#"{"text": "{{ title }}"}"#, false, None);
+        let error = render_payload(&broken, &post()).unwrap_err().to_string();
+        assert!(error.contains("tojson"), "{}", error);
+    }
+
+    #[test]
+    fn test_thread_reply_payload() {
+        let slack = publisher("{{ title }}", true, Some("1712345678.000100"));
+        let payload = slack.message_payload(render_payload(&slack, &post()).unwrap());
+        assert_eq!(payload["channel"], "C123");
+        assert_eq!(payload["thread_ts"], "1712345678.000100");
+        assert_eq!(payload["mrkdwn"], true);
+
+        let slack = publisher("{{ title }}", true, None);
+        let payload = slack.message_payload(render_payload(&slack, &post()).unwrap());
+        assert!(payload.get("thread_ts").is_none());
+    }
+}
@@ -1,10 +1,15 @@
 use super::Publisher;
-use crate::models::{Post, TemplateContext, TemplateRenderer};
+use crate::models::{
+    LengthBudget, OutputEscape, Post, TelegramButton, TemplateContext, TemplateRenderer,
+};
 use anyhow::Result;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde_json::{json, Value};
 
+/// Telegram albums hold 2 to 10 items
+const MAX_MEDIA_GROUP_ITEMS: usize = 10;
+
 pub struct TelegramPublisher {
     #[allow(dead_code)]
     pub id: String,
@@ -13,11 +18,17 @@ pub struct TelegramPublisher {
     pub parse_mode: Option<String>,
     pub message_thread_id: Option<String>,
     pub template: String,
+    pub attach_image: bool,
+    pub buttons: Vec<TelegramButton>,
     client: Client,
+    /// Escapes feed values for `parse_mode`
     renderer: TemplateRenderer,
+    /// Button labels and URLs are not parsed by Telegram
+    plain_renderer: TemplateRenderer,
 }
 
 impl TelegramPublisher {
+    #[expect(clippy::too_many_arguments)]
     pub fn new(
         id: String,
         bot_token: String,
@@ -25,7 +36,11 @@ impl TelegramPublisher {
         parse_mode: Option<String>,
         message_thread_id: Option<String>,
         template: String,
+        attach_image: bool,
+        buttons: Vec<TelegramButton>,
     ) -> Self {
+        let escape = OutputEscape::for_telegram_parse_mode(parse_mode.as_deref());
+
         Self {
             id,
             bot_token,
@@ -33,38 +48,19 @@ impl TelegramPublisher {
             parse_mode,
             message_thread_id,
             template,
+            attach_image,
+            buttons,
             client: Client::new(),
-            renderer: TemplateRenderer::new(),
+            renderer: TemplateRenderer::with_escape(escape),
+            plain_renderer: TemplateRenderer::new(),
         }
     }
-}
-
-#[async_trait]
-impl Publisher for TelegramPublisher {
-    async fn publish(&self, post: &Post) -> Result<String> {
-        let url = format!("https://api.telegram.org/bot{}/sendMessage", self.bot_token);
-
-        let context = TemplateContext {
-            title: post.title.clone(),
-            description: post.description.clone().unwrap_or_default(),
-            url: post.link.clone(),
-        };
-
-        let message = self.renderer.render(&self.template, &context)?;
-
-        // Validate message length (Telegram max is 4096 characters)
-        if message.len() > 4096 {
-            return Err(anyhow::anyhow!(
-                "Message too long for Telegram: {} characters (max 4096)",
-                message.len()
-            ));
-        }
 
+    /// Fields shared by every send method
+    fn base_payload(&self) -> Value {
         let mut payload = json!({
             "chat_id": self.chat_id,
-            "text": message,
-            "parse_mode": self.parse_mode,
-            "disable_web_page_preview": false
+            "parse_mode": self.parse_mode
         });
 
         // Add message_thread_id if specified (for posting to topics)
@@ -80,14 +76,59 @@ impl Publisher for TelegramPublisher {
             }
         }
 
-        let response = self.client.post(&url).json(&payload).send().await?;
+        payload
+    }
+
+    /// Inline keyboard with one row of URL buttons
+    fn reply_markup(&self, context: &TemplateContext) -> Result<Option<Value>> {
+        if self.buttons.is_empty() {
+            return Ok(None);
+        }
+
+        let row = self
+            .buttons
+            .iter()
+            .map(|button| {
+                let text = self.plain_renderer.render(&button.text, context)?;
+                let url = match &button.url {
+                    Some(url_template) => self.plain_renderer.render(url_template, context)?,
+                    None => context.url.clone(),
+                };
+                Ok(json!({ "text": text.trim(), "url": url.trim() }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(json!({ "inline_keyboard": [row] })))
+    }
+
+    /// The lead image followed by any other image enclosures
+    fn images(&self, post: &Post) -> Vec<String> {
+        if !self.attach_image {
+            return Vec::new();
+        }
+
+        let mut images = post.image_url.iter().cloned().collect::<Vec<_>>();
+        for enclosure in &post.enclosures {
+            let is_image = enclosure
+                .mime_type
+                .as_deref()
+                .is_some_and(|mime_type| mime_type.starts_with("image/"));
+            if is_image && !images.contains(&enclosure.url) {
+                images.push(enclosure.url.clone());
+            }
+        }
+        images.truncate(MAX_MEDIA_GROUP_ITEMS);
+        images
+    }
+
+    async fn call(&self, method: &str, payload: &Value) -> Result<Value> {
+        let url = format!("https://api.telegram.org/bot{}/{}", self.bot_token, method);
+
+        let response = self.client.post(&url).json(payload).send().await?;
 
         if response.status().is_success() {
             let result: Value = response.json().await?;
-            Ok(format!(
-                "Published to Telegram: {}",
-                result["result"]["message_id"]
-            ))
+            Ok(result["result"].clone())
         } else {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
@@ -99,6 +140,129 @@ impl Publisher for TelegramPublisher {
         }
     }
 
+    async fn send_photo(
+        &self,
+        image_url: &str,
+        caption: &str,
+        reply_markup: Option<&Value>,
+    ) -> Result<Value> {
+        let mut payload = self.base_payload();
+        payload["photo"] = json!(image_url);
+        payload["caption"] = json!(caption);
+        if let Some(reply_markup) = reply_markup {
+            payload["reply_markup"] = reply_markup.clone();
+        }
+
+        self.call("sendPhoto", &payload).await
+    }
+
+    /// Send an album; the caption goes on the first photo. Albums cannot
+    /// carry an inline keyboard.
+    async fn send_media_group(&self, images: &[String], caption: &str) -> Result<Value> {
+        let media = images
+            .iter()
+            .enumerate()
+            .map(|(index, image_url)| {
+                let mut item = json!({ "type": "photo", "media": image_url });
+                if index == 0 {
+                    item["caption"] = json!(caption);
+                    item["parse_mode"] = json!(self.parse_mode);
+                }
+                item
+            })
+            .collect::<Vec<_>>();
+
+        let mut payload = self.base_payload();
+        payload["media"] = json!(media);
+        // parse_mode is set per item
+        if let Some(payload) = payload.as_object_mut() {
+            payload.remove("parse_mode");
+        }
+
+        let result = self.call("sendMediaGroup", &payload).await?;
+        Ok(result[0].clone())
+    }
+
+    async fn send_text(&self, text: &str, reply_markup: Option<&Value>) -> Result<Value> {
+        let mut payload = self.base_payload();
+        payload["text"] = json!(text);
+        payload["disable_web_page_preview"] = json!(false);
+        if let Some(reply_markup) = reply_markup {
+            payload["reply_markup"] = reply_markup.clone();
+        }
+
+        self.call("sendMessage", &payload).await
+    }
+
+    /// Render the template to fit in one message or caption, shortening the
+    /// description first. With a `parse_mode` the rendered text is never cut,
+    /// so tags and entities stay intact, and `None` means it does not fit.
+    /// Markup counts towards the length, which only errs on the short side.
+    fn render_within(
+        &self,
+        budget: LengthBudget,
+        context: &TemplateContext,
+    ) -> Result<Option<String>> {
+        if self.parse_mode.is_none() {
+            return budget
+                .render(&self.renderer, &self.template, context)
+                .map(Some);
+        }
+
+        let text = budget.render_shortened(&self.renderer, &self.template, context)?;
+        Ok(budget.fits(&text).then_some(text))
+    }
+}
+
+#[async_trait]
+impl Publisher for TelegramPublisher {
+    async fn publish(&self, post: &Post) -> Result<String> {
+        let context = TemplateContext::from_post(post, &self.id);
+
+        let reply_markup = self.reply_markup(&context)?;
+        let images = self.images(post);
+
+        let mut sent = None;
+        if !images.is_empty() {
+            // Captions are limited to 1024 characters: shorten the
+            // description rather than lose the link
+            match self.render_within(LengthBudget::TELEGRAM_CAPTION, &context)? {
+                Some(caption) => {
+                    let result = if images.len() > 1 && reply_markup.is_none() {
+                        self.send_media_group(&images, &caption).await
+                    } else {
+                        self.send_photo(&images[0], &caption, reply_markup.as_ref())
+                            .await
+                    };
+
+                    // A photo Telegram cannot fetch should not block the post itself
+                    match result {
+                        Ok(result) => sent = Some(result),
+                        Err(e) => log::warn!("Posting to Telegram without image: {}", e),
+                    }
+                }
+                None => log::warn!("Caption too long for Telegram, posting without image"),
+            }
+        }
+
+        let result = match sent {
+            Some(result) => result,
+            None => {
+                // One message, so a failure never leaves part of the post behind
+                let message = self
+                    .render_within(LengthBudget::TELEGRAM_MESSAGE, &context)?
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Message too long for Telegram even without the description, content and title"
+                        )
+                    })?;
+                self.send_text(&message, reply_markup.as_ref()).await?
+            }
+        };
+
+        Ok(format!("Published to Telegram: {}", result["message_id"]))
+    }
+
     fn get_type(&self) -> &'static str {
         "telegram"
     }
@@ -111,3 +275,58 @@ impl Publisher for TelegramPublisher {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::LengthRule;
+
+    fn publisher(parse_mode: Option<&str>, template: &str) -> TelegramPublisher {
+        TelegramPublisher::new(
+            "telegram".to_string(),
+            "token".to_string(),
+            "@channel".to_string(),
+            parse_mode.map(str::to_string),
+            None,
+            template.to_string(),
+            true,
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn test_render_within_keeps_html_link() {
+        let budget = LengthBudget {
+            max: 120,
+            rule: LengthRule::Utf16,
+        };
+        let context = TemplateContext {
+            title: "Rust & Go".to_string(),
+            description: "Una descripción larga con <etiquetas> y acentos. ".repeat(10),
+            url: "https://example.com/post".to_string(),
+            ..Default::default()
+        };
+        // The link sits right where a cut at the budget would fall
+        let template = "{{ description }}<a href=\"{{ url }}\">{{ title }}</a>";
+
+        let html = publisher(Some("HTML"), template);
+        let text = html.render_within(budget, &context).unwrap().unwrap();
+        assert!(budget.fits(&text), "{}", text);
+        assert!(text.starts_with("Una descripción"), "{}", text);
+        assert!(
+            text.ends_with("…<a href=\"https://example.com/post\">Rust &amp; Go</a>"),
+            "{}",
+            text
+        );
+        assert!(!text.contains("&lt…") && !text.contains("<etiquetas"));
+
+        // Without room for the markup nothing is sent rather than a broken tag
+        let tiny = LengthBudget { max: 30, ..budget };
+        assert_eq!(html.render_within(tiny, &context).unwrap(), None);
+
+        // Plain text can still be cut
+        let plain = publisher(None, template);
+        let text = plain.render_within(tiny, &context).unwrap().unwrap();
+        assert!(tiny.fits(&text) && text.ends_with('…'), "{}", text);
+    }
+}
@@ -1,5 +1,5 @@
 use super::Publisher;
-use crate::models::{Post, TemplateContext, TemplateRenderer};
+use crate::models::{LengthBudget, Post, TemplateContext, TemplateRenderer};
 use anyhow::Result;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -37,20 +37,10 @@ impl Publisher for ThreadsPublisher {
         // Step 1: Create media container
         let container_url = format!("https://graph.threads.net/v1.0/{}/threads", self.user_id);
 
-        let context = TemplateContext {
-            title: post.title.clone(),
-            description: post.description.clone().unwrap_or_default(),
-            url: post.link.clone(),
-        };
-
-        let text = self.renderer.render(&self.template, &context)?;
+        let context = TemplateContext::from_post(post, &self.id);
 
         // Threads has a character limit of 500
-        let text = if text.len() > 500 {
-            format!("{}...", &text[..497])
-        } else {
-            text
-        };
+        let text = LengthBudget::THREADS.render(&self.renderer, &self.template, &context)?;
 
         let container_payload = json!({
             "media_type": "TEXT",
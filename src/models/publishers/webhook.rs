@@ -0,0 +1,233 @@
+use super::Publisher;
+use crate::models::{Post, TemplateContext, TemplateRenderer};
+use anyhow::Result;
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::{Client, Method};
+use std::collections::HashMap;
+
+pub struct WebhookPublisher {
+    #[allow(dead_code)]
+    pub id: String,
+    pub url: String,
+    pub method: Method,
+    pub headers: HeaderMap,
+    pub template: String,
+    pub success_status_codes: Option<Vec<u16>>,
+    client: Client,
+    renderer: TemplateRenderer,
+}
+
+impl WebhookPublisher {
+    pub fn new(
+        id: String,
+        url: String,
+        method: Option<String>,
+        headers: Option<HashMap<String, String>>,
+        template: String,
+        success_status_codes: Option<Vec<u16>>,
+    ) -> Result<Self> {
+        let method = match method {
+            Some(method) => Method::from_bytes(method.to_uppercase().as_bytes())
+                .map_err(|_| anyhow::anyhow!("Invalid HTTP method for webhook: {}", method))?,
+            None => Method::POST,
+        };
+
+        let mut header_map = HeaderMap::new();
+        header_map.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        for (name, value) in headers.unwrap_or_default() {
+            let value = expand_env_vars(&value)
+                .map_err(|e| anyhow::anyhow!("Webhook header '{}': {}", name, e))?;
+            header_map.insert(
+                HeaderName::from_bytes(name.as_bytes())
+                    .map_err(|_| anyhow::anyhow!("Invalid webhook header name: {}", name))?,
+                HeaderValue::from_str(&value)
+                    .map_err(|_| anyhow::anyhow!("Invalid value for webhook header: {}", name))?,
+            );
+        }
+
+        Ok(Self {
+            id,
+            url,
+            method,
+            headers: header_map,
+            template,
+            success_status_codes,
+            client: Client::new(),
+            renderer: TemplateRenderer::new(),
+        })
+    }
+
+    fn is_success(&self, status: reqwest::StatusCode) -> bool {
+        match &self.success_status_codes {
+            Some(codes) => codes.contains(&status.as_u16()),
+            None => status.is_success(),
+        }
+    }
+
+    fn is_json(&self) -> bool {
+        self.headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.to_ascii_lowercase().contains("json"))
+    }
+
+    /// Render the request body. A JSON body that does not parse is rejected
+    /// here rather than sent, since values are inserted as they are: a quote or
+    /// newline in a title breaks the document unless the template uses `tojson`.
+    fn render_body(&self, post: &Post) -> Result<String> {
+        let context = TemplateContext::from_post(post, &self.id);
+        let body = self.renderer.render(&self.template, &context)?;
+
+        if self.is_json() {
+            serde_json::from_str::<serde_json::Value>(&body).map_err(|e| {
+                anyhow::anyhow!(
+                    "Webhook body is not valid JSON ({}); use the tojson filter for values",
+                    e
+                )
+            })?;
+        }
+
+        Ok(body)
+    }
+}
+
+/// Replace every `${VAR}` with the value of the environment variable VAR
+fn expand_env_vars(value: &str) -> Result<String> {
+    let env_regex = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+    let mut result = String::with_capacity(value.len());
+    let mut last_end = 0;
+
+    for caps in env_regex.captures_iter(value) {
+        let whole = caps.get(0).unwrap();
+        let name = &caps[1];
+        let var = std::env::var(name)
+            .map_err(|_| anyhow::anyhow!("environment variable {} is not set", name))?;
+        result.push_str(&value[last_end..whole.start()]);
+        result.push_str(&var);
+        last_end = whole.end();
+    }
+
+    result.push_str(&value[last_end..]);
+    Ok(result)
+}
+
+#[async_trait]
+impl Publisher for WebhookPublisher {
+    async fn publish(&self, post: &Post) -> Result<String> {
+        let body = self.render_body(post)?;
+
+        let mut request = self
+            .client
+            .request(self.method.clone(), &self.url)
+            .headers(self.headers.clone());
+
+        // GET and HEAD requests carry no body
+        if self.method != Method::GET && self.method != Method::HEAD {
+            request = request.body(body);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+
+        if self.is_success(status) {
+            Ok(format!("Published to webhook: {}", status))
+        } else {
+            let error_text = response.text().await.unwrap_or_default();
+            Err(anyhow::anyhow!(
+                "Failed to publish to webhook: {} - {}",
+                status,
+                error_text
+            ))
+        }
+    }
+
+    fn get_type(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use reqwest::StatusCode;
+
+    fn publisher(template: &str, headers: &[(&str, &str)]) -> WebhookPublisher {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        WebhookPublisher::new(
+            "hook".to_string(),
+            "https://example.com/hook".to_string(),
+            None,
+            Some(headers),
+            template.to_string(),
+            Some(vec![200, 202]),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_expand_env_vars() {
+        std::env::set_var("POPULATRS_TEST_WEBHOOK_TOKEN", "s3cret");
+        assert_eq!(
+            expand_env_vars("Bearer ${POPULATRS_TEST_WEBHOOK_TOKEN}!").unwrap(),
+            "Bearer s3cret!"
+        );
+        assert_eq!(expand_env_vars("no vars $HOME").unwrap(), "no vars $HOME");
+
+        let error = expand_env_vars("${POPULATRS_TEST_WEBHOOK_MISSING}").unwrap_err();
+        assert!(
+            error.to_string().contains("POPULATRS_TEST_WEBHOOK_MISSING"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_success_status_codes() {
+        let custom = publisher("{}", &[]);
+        assert!(custom.is_success(StatusCode::ACCEPTED));
+        assert!(!custom.is_success(StatusCode::CREATED));
+
+        let mut default = publisher("{}", &[]);
+        default.success_status_codes = None;
+        assert!(default.is_success(StatusCode::CREATED));
+        assert!(!default.is_success(StatusCode::FOUND));
+    }
+
+    #[test]
+    fn test_json_body_with_quoted_title() {
+        let mut post = Post::for_test("feed", "a", Utc::now());
+        post.title = "Say \"hi\"\n\\o/".to_string();
+
+        let body = publisher(r#"{"title": {{ title | tojson }}}"#, &[])
+            .render_body(&post)
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["title"], post.title);
+
+        let error = publisher(r#"{"title": "{{ title }}"}"#, &[])
+            .render_body(&post)
+            .unwrap_err();
+        assert!(error.to_string().contains("tojson"), "{}", error);
+
+        // Bodies that are not JSON are sent as rendered
+        let plain = publisher(r#"title="{{ title }}""#, &[("Content-Type", "text/plain")]);
+        assert_eq!(
+            plain.render_body(&post).unwrap(),
+            format!("title=\"{}\"", post.title)
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use super::Publisher;
-use crate::models::{Post, TemplateContext, TemplateRenderer};
+use crate::models::{LengthBudget, Post, TemplateContext, TemplateRenderer};
 use crate::storage::StorageManager;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -313,20 +313,10 @@ impl XPublisher {
 #[async_trait]
 impl Publisher for XPublisher {
     async fn publish(&self, post: &Post) -> Result<String> {
-        let context = TemplateContext {
-            title: post.title.clone(),
-            description: post.description.clone().unwrap_or_default(),
-            url: post.link.clone(),
-        };
-
-        let tweet_text = self.renderer.render(&self.template, &context)?;
+        let context = TemplateContext::from_post(post, &self.id);
 
-        // Truncate to Twitter's character limit
-        let tweet_text = if tweet_text.len() > 280 {
-            format!("{}...", &tweet_text[..277])
-        } else {
-            tweet_text
-        };
+        // Fit X's length limit by shortening the description and keeping the URL
+        let tweet_text = LengthBudget::X.render(&self.renderer, &self.template, &context)?;
 
         log::info!(
             "Attempting to publish to X with OAuth 2.0: '{}'",
@@ -0,0 +1,205 @@
+use crate::models::{Post, RetryConfig};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A single failed delivery of a post to one publisher, waiting to be retried
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryEntry {
+    pub post: Post,
+    pub publisher_id: String,
+    pub attempts: u32,
+    pub last_error: String,
+    pub first_failed_at: DateTime<Utc>,
+    pub next_attempt_at: DateTime<Utc>,
+}
+
+/// Persistent queue of (post, publisher) pairs whose delivery failed
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RetryQueue {
+    pub entries: Vec<RetryEntry>,
+}
+
+impl RetryQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a failed delivery. Returns false when retries are disabled and the
+    /// delivery was dropped instead.
+    pub fn enqueue(
+        &mut self,
+        post: &Post,
+        publisher_id: &str,
+        error: String,
+        config: &RetryConfig,
+    ) -> bool {
+        if config.max_attempts <= 1 {
+            return false;
+        }
+
+        // A post is only delivered once per publisher, so never queue it twice
+        if self.contains(post, publisher_id) {
+            return true;
+        }
+
+        let now = Utc::now();
+        self.entries.push(RetryEntry {
+            post: post.clone(),
+            publisher_id: publisher_id.to_string(),
+            attempts: 1,
+            last_error: error,
+            first_failed_at: now,
+            next_attempt_at: now + Self::backoff(1, config),
+        });
+        true
+    }
+
+    /// Remove and return every entry whose next attempt is due
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> Vec<RetryEntry> {
+        let (due, pending): (Vec<_>, Vec<_>) = self
+            .entries
+            .drain(..)
+            .partition(|e| e.next_attempt_at <= now);
+        self.entries = pending;
+        due
+    }
+
+    /// Put an entry back after another failed attempt. Returns false when the
+    /// entry has used up its attempts and was dropped.
+    pub fn reschedule(
+        &mut self,
+        mut entry: RetryEntry,
+        error: String,
+        config: &RetryConfig,
+    ) -> bool {
+        entry.attempts += 1;
+        entry.last_error = error;
+
+        if entry.attempts >= config.max_attempts {
+            return false;
+        }
+
+        entry.next_attempt_at = Utc::now() + Self::backoff(entry.attempts, config);
+        self.entries.push(entry);
+        true
+    }
+
+    /// Whether any entry is due for another attempt
+    pub fn has_due(&self, now: DateTime<Utc>) -> bool {
+        self.entries.iter().any(|e| e.next_attempt_at <= now)
+    }
+
+    pub fn contains(&self, post: &Post, publisher_id: &str) -> bool {
+        self.entries.iter().any(|e| {
+            e.post.guid == post.guid
+                && e.post.feed_id == post.feed_id
+                && e.publisher_id == publisher_id
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Exponential backoff: base_delay * 2^(attempts - 1), capped at max_delay
+    fn backoff(attempts: u32, config: &RetryConfig) -> Duration {
+        let factor = 2_u64.saturating_pow(attempts.saturating_sub(1));
+        let minutes = config
+            .base_delay_minutes
+            .saturating_mul(factor)
+            .min(config.max_delay_minutes);
+        Duration::minutes(minutes as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RetryConfig {
+        RetryConfig {
+            max_attempts: 3,
+            base_delay_minutes: 5,
+            max_delay_minutes: 60,
+        }
+    }
+
+    fn post(guid: &str) -> Post {
+        Post::for_test("feed", guid, Utc::now())
+    }
+
+    #[test]
+    fn test_backoff() {
+        let config = config();
+        assert_eq!(RetryQueue::backoff(1, &config), Duration::minutes(5));
+        assert_eq!(RetryQueue::backoff(2, &config), Duration::minutes(10));
+        assert_eq!(RetryQueue::backoff(4, &config), Duration::minutes(40));
+        // Capped at max_delay_minutes, without overflowing
+        assert_eq!(RetryQueue::backoff(5, &config), Duration::minutes(60));
+        assert_eq!(RetryQueue::backoff(200, &config), Duration::minutes(60));
+    }
+
+    #[test]
+    fn test_take_due() {
+        let config = config();
+        let mut queue = RetryQueue::new();
+        assert!(queue.enqueue(&post("a"), "telegram", "500".to_string(), &config));
+        assert!(queue.enqueue(&post("b"), "telegram", "500".to_string(), &config));
+        // Queued once per publisher
+        assert!(queue.enqueue(&post("a"), "telegram", "500".to_string(), &config));
+        assert_eq!(queue.len(), 2);
+
+        queue.entries[0].next_attempt_at = Utc::now() - Duration::minutes(1);
+        assert!(queue.has_due(Utc::now()));
+
+        let due = queue.take_due(Utc::now());
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].post.guid, "a");
+        assert_eq!(queue.len(), 1);
+        assert!(!queue.has_due(Utc::now()));
+    }
+
+    #[test]
+    fn test_reschedule_drops_after_max_attempts() {
+        let config = config();
+        let mut queue = RetryQueue::new();
+        queue.enqueue(&post("a"), "x", "first".to_string(), &config);
+
+        let entry = queue.take_due(Utc::now() + Duration::days(1)).remove(0);
+        assert!(queue.reschedule(entry, "second".to_string(), &config));
+        assert_eq!(queue.entries[0].attempts, 2);
+        assert_eq!(queue.entries[0].last_error, "second");
+        assert!(queue.entries[0].next_attempt_at > Utc::now() + Duration::minutes(9));
+
+        let entry = queue.take_due(Utc::now() + Duration::days(1)).remove(0);
+        assert!(!queue.reschedule(entry, "third".to_string(), &config));
+        assert!(queue.is_empty());
+
+        // Retries disabled
+        let config = RetryConfig {
+            max_attempts: 1,
+            ..config
+        };
+        assert!(!queue.enqueue(&post("a"), "x", "first".to_string(), &config));
+    }
+
+    #[test]
+    fn test_queue_persistence() {
+        let mut queue = RetryQueue::new();
+        queue.enqueue(&post("a"), "x", "500".to_string(), &config());
+
+        let json = serde_json::to_string(&queue).unwrap();
+        let loaded: RetryQueue = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.entries[0].post, queue.entries[0].post);
+        assert_eq!(
+            loaded.entries[0].next_attempt_at,
+            queue.entries[0].next_attempt_at
+        );
+        assert!(loaded.contains(&post("a"), "x"));
+    }
+}
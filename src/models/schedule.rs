@@ -0,0 +1,47 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use croner::parser::{CronParser, Seconds};
+use croner::Cron;
+
+/// Parse a cron expression with seconds (`sec min hour day month weekday`),
+/// with the same rules as the scheduler's own jobs
+pub fn parse_cron(expression: &str) -> Result<Cron> {
+    CronParser::builder()
+        .seconds(Seconds::Required)
+        .dom_and_dow(true)
+        .build()
+        .parse(expression)
+        .map_err(|e| anyhow::anyhow!("Invalid cron expression '{}': {}", expression, e))
+}
+
+/// First time after `after` that the expression matches in `timezone`
+pub fn next_run(cron: &Cron, after: DateTime<Utc>, timezone: Tz) -> Option<DateTime<Utc>> {
+    cron.find_next_occurrence(&after.with_timezone(&timezone), false)
+        .ok()
+        .map(|next| next.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_run_in_timezone() {
+        let cron = parse_cron("0 0 8 * * *").unwrap();
+        let after = "2024-10-21T07:30:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        // 08:00 in Madrid is 06:00 UTC in October (CEST), so the next is tomorrow's
+        assert_eq!(
+            next_run(&cron, after, chrono_tz::Europe::Madrid),
+            Some("2024-10-22T06:00:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            next_run(&cron, after, Tz::UTC),
+            Some("2024-10-21T08:00:00Z".parse().unwrap())
+        );
+        assert!(parse_cron("0 0 9,18 * * Mon-Fri").is_ok());
+        // Five fields are rejected rather than read with a different meaning
+        assert!(parse_cron("0 8 * * *").is_err());
+    }
+}
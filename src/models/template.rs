@@ -1,13 +1,99 @@
+use crate::models::{Enclosure, Post};
 use anyhow::{Context, Result};
-use minijinja::{Environment, Value};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use minijinja::{AutoEscape, Environment, ErrorKind, Value};
 use serde_json::json;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Zona horaria de `schedule.timezone`, usada al formatear fechas
+static TIMEZONE: OnceLock<Tz> = OnceLock::new();
+
+/// Configura la zona horaria de los templates y de las expresiones cron
+/// (solo tiene efecto la primera vez)
+pub fn set_timezone(timezone: Tz) {
+    let _ = TIMEZONE.set(timezone);
+}
+
+pub fn timezone() -> Tz {
+    TIMEZONE.get().copied().unwrap_or(Tz::UTC)
+}
 
 /// Estructura que contiene los datos para el renderizado de templates
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TemplateContext {
     pub title: String,
     pub description: String,
     pub url: String,
+    pub content: String,
+    pub guid: String,
+    pub published: Option<DateTime<Utc>>,
+    pub author: Option<String>,
+    pub categories: Vec<String>,
+    pub image_url: Option<String>,
+    pub enclosures: Vec<Enclosure>,
+    pub language: Option<String>,
+    pub feed_id: String,
+    pub feed_name: String,
+    pub feed_url: String,
+    pub hashtags: Vec<String>,
+    pub publisher_id: String,
+}
+
+impl TemplateContext {
+    /// Crea el contexto a partir de un post y del publisher que lo va a publicar
+    pub fn from_post(post: &Post, publisher_id: &str) -> Self {
+        Self {
+            title: post.title.clone(),
+            description: post.description.clone().unwrap_or_default(),
+            url: post.link.clone(),
+            content: post
+                .content
+                .clone()
+                .or_else(|| post.description.clone())
+                .unwrap_or_default(),
+            guid: post.guid.clone(),
+            published: Some(post.published),
+            author: post.author.clone(),
+            categories: post.categories.clone(),
+            image_url: post.image_url.clone(),
+            enclosures: post.enclosures.clone(),
+            language: post.language.clone(),
+            feed_id: post.feed_id.clone(),
+            feed_name: post.feed_name.clone(),
+            feed_url: post.feed_url.clone(),
+            hashtags: post.hashtags.clone(),
+            publisher_id: publisher_id.to_string(),
+        }
+    }
+}
+
+/// Escapado aplicado a cada expresión `{{ }}` de un template
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputEscape {
+    #[default]
+    None,
+    /// `&`, `<`, `>` y `"` como entidades HTML
+    Html,
+    /// Caracteres reservados de Telegram MarkdownV2 precedidos de `\`
+    MarkdownV2,
+    /// `&`, `<` y `>` como entidades, para el mrkdwn de Slack
+    Slack,
+}
+
+impl OutputEscape {
+    /// Escapado que necesita un `parse_mode` de Telegram
+    pub fn for_telegram_parse_mode(parse_mode: Option<&str>) -> Self {
+        match parse_mode.map(str::to_ascii_lowercase).as_deref() {
+            Some("html") => Self::Html,
+            Some("markdownv2") => Self::MarkdownV2,
+            _ => Self::None,
+        }
+    }
 }
 
 /// Renderizador de templates que utiliza MiniliJinja
@@ -18,12 +104,46 @@ pub struct TemplateRenderer {
 impl TemplateRenderer {
     /// Crea una nueva instancia del renderizador
     pub fn new() -> Self {
+        Self::with_escape(OutputEscape::None)
+    }
+
+    /// Crea un renderizador que escapa los valores insertados en el template.
+    /// El texto literal del template no se toca y `| safe` evita el escapado.
+    pub fn with_escape(escape: OutputEscape) -> Self {
         let mut env = Environment::new();
 
+        if escape != OutputEscape::None {
+            env.set_auto_escape_callback(move |_| match escape {
+                OutputEscape::None => AutoEscape::None,
+                OutputEscape::Html => AutoEscape::Custom("html"),
+                OutputEscape::MarkdownV2 => AutoEscape::Custom("markdown_v2"),
+                OutputEscape::Slack => AutoEscape::Custom("slack"),
+            });
+            env.set_formatter(|out, state, value| {
+                let escape_fn: fn(&str) -> String = match state.auto_escape() {
+                    AutoEscape::Custom("html") => escape_html,
+                    AutoEscape::Custom("markdown_v2") => escape_markdown_v2,
+                    AutoEscape::Custom("slack") => escape_slack,
+                    _ => return minijinja::escape_formatter(out, state, value),
+                };
+                if value.is_safe() || value.is_undefined() {
+                    return write!(out, "{}", value).map_err(minijinja::Error::from);
+                }
+                out.write_str(&escape_fn(&value.to_string()))
+                    .map_err(minijinja::Error::from)
+            });
+        }
+
         // Añadir filtros útiles para templates
         env.add_filter("truncate", truncate_function);
         env.add_filter("word_limit", word_limit_function);
         env.add_filter("strip_html", strip_html_function);
+        env.add_filter("date", date_function);
+        env.add_filter("datetime", datetime_function);
+        env.add_filter("hashtags", hashtags_function);
+        env.add_filter("escape_html", escape_html_function);
+        env.add_filter("escape_markdown_v2", escape_markdown_v2_function);
+        env.add_filter("escape_slack", escape_slack_function);
 
         Self { env }
     }
@@ -35,10 +155,28 @@ impl TemplateRenderer {
             .template_from_str(template)
             .context("Failed to parse template")?;
 
+        let published = context
+            .published
+            .map(|published| published.with_timezone(&timezone()));
+
         let template_vars = json!({
             "title": context.title,
             "description": context.description,
-            "url": context.url
+            "url": context.url,
+            "content": context.content,
+            "guid": context.guid,
+            "published": published.map(|published| published.to_rfc3339()),
+            "published_date": published.map(|published| published.format(DEFAULT_DATE_FORMAT).to_string()),
+            "author": context.author,
+            "categories": context.categories,
+            "image_url": context.image_url,
+            "enclosures": context.enclosures,
+            "language": context.language,
+            "feed_id": context.feed_id,
+            "feed_name": context.feed_name,
+            "feed_url": context.feed_url,
+            "hashtags": context.hashtags,
+            "publisher_id": context.publisher_id
         });
 
         let rendered = tmpl
@@ -51,15 +189,28 @@ impl TemplateRenderer {
     /// Obtiene el template por defecto para un tipo de publisher específico
     pub fn get_default_template(publisher_type: &str) -> String {
         match publisher_type {
-            "telegram" => "**{{ title }}**\n\n{{ description | truncate(480) }}\n\n🔗 [Leer más]({{ url }})".to_string(),
+            "telegram" => "{{ title }}\n\n{{ description | strip_html | truncate(480) }}\n\n🔗 {{ url }}".to_string(),
+            "telegram_html" => "<b>{{ title }}</b>\n\n{{ description | strip_html | truncate(480) }}\n\n🔗 <a href=\"{{ url }}\">Leer más</a>".to_string(),
+            "telegram_markdown_v2" => "*{{ title }}*\n\n{{ description | strip_html | truncate(480) }}\n\n🔗 [Leer más]({{ url | escape_markdown_v2(\"url\") }})".to_string(),
             "x" => "{{ title | truncate(240) }}\n\n{{ url }}".to_string(),
             "mastodon" => "{{ title }}\n\n{{ description | truncate(400) }}\n\n{{ url }}".to_string(),
+            "mastodon_alt_text" => "{{ title }}".to_string(),
             "linkedin" => "{{ title }}\n\n{{ description | truncate(700) }}\n\nLeer más: {{ url }}".to_string(),
             "matrix" => "<h3>{{ title }}</h3><p>{{ description | truncate(500) }}</p><p><a href=\"{{ url }}\">Leer más</a></p>".to_string(),
             "bluesky" => "{{ title | truncate(250) }}\n\n{{ url }}".to_string(),
             "threads" => "{{ title }}\n\n{{ description | truncate(450) }}\n\n{{ url }}".to_string(),
+            "slack" => "*<{{ url }}|{{ title }}>*\n\n{{ description | truncate(400) }}".to_string(),
             "discord" => "**{{ title }}**\n\n{{ description | truncate(400) }}\n\n🔗 {{ url }}".to_string(),
+            // El embed ya muestra el título, la descripción y el enlace
+            "discord_embed" => "".to_string(),
+            "discord_embed_description" => "{{ description | strip_html | truncate(400) }}".to_string(),
             "openobserve" => "Feed: {{ title }}\nDescription: {{ description }}\nURL: {{ url }}".to_string(),
+            "email_subject" => "{{ title }}".to_string(),
+            "email" => "<h2><a href=\"{{ url }}\">{{ title }}</a></h2>\n<div>{{ description | safe }}</div>\n<p><a href=\"{{ url }}\">Leer más</a></p>".to_string(),
+            "ntfy_title" | "gotify_title" => "{{ title }}".to_string(),
+            "ntfy" => "{{ description | strip_html | truncate(400) }}".to_string(),
+            "gotify" => "{{ description | strip_html | truncate(400) }}\n\n[Leer más]({{ url }})".to_string(),
+            "webhook" => "{\"title\": {{ title | tojson }}, \"description\": {{ description | tojson }}, \"url\": {{ url | tojson }}}".to_string(),
             _ => "{{ title }}\n\n{{ description }}\n\n{{ url }}".to_string(),
         }
     }
@@ -111,9 +262,12 @@ fn word_limit_function(value: Value, limit: Value) -> Result<Value, minijinja::E
 }
 
 /// Función básica para eliminar tags HTML
-fn strip_html_function(value: Value, _args: Value) -> Result<Value, minijinja::Error> {
-    let text = value.as_str().unwrap_or("");
+fn strip_html_function(value: Value, _args: Option<Value>) -> Result<Value, minijinja::Error> {
+    Ok(Value::from(strip_html(value.as_str().unwrap_or(""))))
+}
 
+/// Elimina los tags HTML de un texto dejando los saltos de línea básicos
+pub fn strip_html(text: &str) -> String {
     // Básica eliminación de tags HTML
     let mut result = text.to_string();
 
@@ -134,14 +288,217 @@ fn strip_html_function(value: Value, _args: Value) -> Result<Value, minijinja::E
     }
 
     // Limpiar espacios múltiples y saltos de línea excesivos
-    result = result
+    result
         .lines()
         .map(|line| line.trim())
         .filter(|line| !line.is_empty())
         .collect::<Vec<_>>()
-        .join("\n");
+        .join("\n")
+}
 
-    Ok(Value::from(result))
+/// Filtro `escape_html`; el resultado no se vuelve a escapar
+fn escape_html_function(value: Value) -> Result<Value, minijinja::Error> {
+    Ok(Value::from_safe_string(escape_html(&value.to_string())))
+}
+
+/// Filtro `escape_slack`; el resultado no se vuelve a escapar
+fn escape_slack_function(value: Value) -> Result<Value, minijinja::Error> {
+    Ok(Value::from_safe_string(escape_slack(&value.to_string())))
+}
+
+/// Filtro `escape_markdown_v2`. Con `"url"` escapa solo lo necesario dentro
+/// del `(...)` de un enlace y con `"code"` o `"pre"` dentro de un bloque de código
+fn escape_markdown_v2_function(
+    value: Value,
+    entity: Option<String>,
+) -> Result<Value, minijinja::Error> {
+    let text = value.to_string();
+    let escaped = match entity.as_deref() {
+        None => escape_markdown_v2(&text),
+        Some("url") => escape_chars(&text, &['\\', ')']),
+        Some("code") | Some("pre") => escape_chars(&text, &['\\', '`']),
+        Some(other) => {
+            return Err(minijinja::Error::new(
+                ErrorKind::InvalidOperation,
+                format!("unknown MarkdownV2 entity '{}'", other),
+            ))
+        }
+    };
+    Ok(Value::from_safe_string(escaped))
+}
+
+/// Escapa un texto para el `parse_mode` HTML de Telegram
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escapa los caracteres de control del mrkdwn de Slack. Así un `>` o un `&`
+/// en el título no rompe un enlace `<url|título>`
+pub fn escape_slack(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escapa los caracteres reservados de Telegram MarkdownV2
+pub fn escape_markdown_v2(text: &str) -> String {
+    escape_chars(
+        text,
+        &[
+            '\\', '_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}',
+            '.', '!',
+        ],
+    )
+}
+
+fn escape_chars(text: &str, special: &[char]) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if special.contains(&c) {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Convierte una lista de tags (o un texto separado por comas) en hashtags
+/// separados por espacios, opcionalmente limitados a `max`
+fn hashtags_function(value: Value, max: Option<usize>) -> Result<Value, minijinja::Error> {
+    let tags: Vec<String> = if let Some(text) = value.as_str() {
+        text.split(',').map(str::to_string).collect()
+    } else {
+        value
+            .try_iter()?
+            .map(|tag| tag.as_str().map(str::to_string).unwrap_or(tag.to_string()))
+            .collect()
+    };
+
+    let mut hashtags: Vec<String> = Vec::new();
+    for hashtag in tags.iter().filter_map(|tag| to_hashtag(tag)) {
+        if !hashtags.contains(&hashtag) {
+            hashtags.push(hashtag);
+        }
+    }
+    hashtags.truncate(max.unwrap_or(usize::MAX));
+
+    Ok(Value::from(hashtags.join(" ")))
+}
+
+/// Convierte un tag en un hashtag válido para cualquier plataforma:
+/// "rust-lang" -> "#RustLang", "Programación web" -> "#ProgramacionWeb"
+pub fn to_hashtag(tag: &str) -> Option<String> {
+    // Quitar acentos descomponiendo los caracteres y eliminando las marcas
+    let plain = tag
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .collect::<String>();
+
+    let body = plain
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<String>();
+
+    // Un hashtag solo con números no funciona en la mayoría de plataformas
+    if body.is_empty() || body.chars().all(|c| c.is_ascii_digit() || c == '_') {
+        return None;
+    }
+
+    Some(format!("#{}", body))
+}
+
+/// Construye los hashtags de un post aplicando la tabla de equivalencias del
+/// feed (sin distinguir mayúsculas) y añadiendo sus hashtags fijos
+pub fn build_hashtags(
+    categories: &[String],
+    hashtag_map: &HashMap<String, String>,
+    extra_hashtags: &[String],
+) -> Vec<String> {
+    let mut hashtags: Vec<String> = Vec::new();
+
+    let mapped = categories.iter().map(|category| {
+        let category_key = category.trim().to_lowercase();
+        hashtag_map
+            .iter()
+            .find(|(key, _)| key.trim().to_lowercase() == category_key)
+            .map(|(_, hashtag)| hashtag.as_str())
+            .unwrap_or(category)
+    });
+
+    for hashtag in mapped
+        .chain(extra_hashtags.iter().map(String::as_str))
+        .filter_map(to_hashtag)
+    {
+        if !hashtags.contains(&hashtag) {
+            hashtags.push(hashtag);
+        }
+    }
+
+    hashtags
+}
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+const DEFAULT_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Formatea una fecha en la zona horaria configurada (por defecto `%Y-%m-%d`)
+fn date_function(value: Value, format: Option<String>) -> Result<Value, minijinja::Error> {
+    format_date(&value, format.as_deref().unwrap_or(DEFAULT_DATE_FORMAT))
+}
+
+/// Formatea fecha y hora en la zona horaria configurada (por defecto `%Y-%m-%d %H:%M`)
+fn datetime_function(value: Value, format: Option<String>) -> Result<Value, minijinja::Error> {
+    format_date(&value, format.as_deref().unwrap_or(DEFAULT_DATETIME_FORMAT))
+}
+
+/// Acepta fechas RFC 3339 o timestamps Unix
+fn format_date(value: &Value, format: &str) -> Result<Value, minijinja::Error> {
+    let date = if let Some(text) = value.as_str() {
+        DateTime::parse_from_rfc3339(text)
+            .map(|date| date.with_timezone(&Utc))
+            .map_err(|e| {
+                minijinja::Error::new(
+                    ErrorKind::InvalidOperation,
+                    format!("invalid date '{}': {}", text, e),
+                )
+            })?
+    } else if let Some(timestamp) = value.as_i64() {
+        DateTime::from_timestamp(timestamp, 0).ok_or_else(|| {
+            minijinja::Error::new(
+                ErrorKind::InvalidOperation,
+                format!("invalid timestamp {}", timestamp),
+            )
+        })?
+    } else {
+        return Err(minijinja::Error::new(
+            ErrorKind::InvalidOperation,
+            "date filters expect an RFC 3339 string or a timestamp",
+        ));
+    };
+
+    // chrono entra en pánico al mostrar un formato inválido, así que se valida antes
+    let items = StrftimeItems::new(format).collect::<Vec<_>>();
+    if items.contains(&Item::Error) {
+        return Err(minijinja::Error::new(
+            ErrorKind::InvalidOperation,
+            format!("invalid date format '{}'", format),
+        ));
+    }
+
+    Ok(Value::from(
+        date.with_timezone(&timezone())
+            .format_with_items(items.iter())
+            .to_string(),
+    ))
 }
 
 #[cfg(test)]
@@ -155,6 +512,7 @@ mod tests {
             title: "Test Title".to_string(),
             description: "Test Description".to_string(),
             url: "https://example.com".to_string(),
+            ..Default::default()
         };
 
         let template = "{{ title }}: {{ description }}";
@@ -169,6 +527,7 @@ mod tests {
             title: "Very Long Title That Should Be Truncated".to_string(),
             description: "Description".to_string(),
             url: "https://example.com".to_string(),
+            ..Default::default()
         };
 
         let template = "{{ title | truncate(20) }}";
@@ -176,4 +535,123 @@ mod tests {
         assert!(result.len() <= 24); // 20 + "..." = 23, but accounting for word boundaries
         assert!(result.contains("..."));
     }
+
+    #[test]
+    fn test_post_context_variables() {
+        let mut post = Post::new(
+            "guid-1".to_string(),
+            "Title".to_string(),
+            Some("Description".to_string()),
+            "https://example.com/post".to_string(),
+            DateTime::parse_from_rfc3339("2024-03-01T10:30:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            "blog".to_string(),
+        );
+        post.feed_name = "My Blog".to_string();
+        post.author = Some("Ana".to_string());
+        post.categories = vec!["rust".to_string(), "linux".to_string()];
+
+        let renderer = TemplateRenderer::new();
+        let context = TemplateContext::from_post(&post, "mastodon-main");
+
+        let template = "{{ feed_name }}|{{ author }}|{{ categories | join(',') }}|{{ publisher_id }}|{{ published | date('%d/%m/%Y') }}";
+        let result = renderer.render(template, &context).unwrap();
+        assert_eq!(result, "My Blog|Ana|rust,linux|mastodon-main|01/03/2024");
+    }
+
+    #[test]
+    fn test_output_escape() {
+        let context = TemplateContext {
+            title: "<Rust> & *Go_lang* v1.2!".to_string(),
+            url: "https://example.com/a_(b)".to_string(),
+            ..Default::default()
+        };
+
+        let html = TemplateRenderer::with_escape(OutputEscape::Html)
+            .render(
+                "<b>{{ title }}</b> <a href=\"{{ url }}\">{{ title | truncate(6) }}</a>",
+                &context,
+            )
+            .unwrap();
+        assert_eq!(
+            html,
+            "<b>&lt;Rust&gt; &amp; *Go_lang* v1.2!</b> <a href=\"https://example.com/a_(b)\">&lt;Rust&gt;...</a>"
+        );
+
+        let markdown = TemplateRenderer::with_escape(OutputEscape::MarkdownV2)
+            .render(
+                "*{{ title }}* [link]({{ url | escape_markdown_v2(\"url\") }})",
+                &context,
+            )
+            .unwrap();
+        assert_eq!(
+            markdown,
+            "*<Rust\\> & \\*Go\\_lang\\* v1\\.2\\!* [link](https://example.com/a_(b\\))"
+        );
+
+        let slack = TemplateRenderer::with_escape(OutputEscape::Slack);
+        assert_eq!(
+            slack.render("*<{{ url }}|{{ title }}>*", &context).unwrap(),
+            "*<https://example.com/a_(b)|&lt;Rust&gt; &amp; *Go_lang* v1.2!>*"
+        );
+        // `tojson` no pasa por el escapado automático, hay que usar el filtro
+        let json = slack
+            .render("{{ (title | escape_slack) | tojson }}", &context)
+            .unwrap();
+        assert_eq!(
+            serde_json::from_str::<String>(&json).unwrap(),
+            "&lt;Rust&gt; &amp; *Go_lang* v1.2!"
+        );
+
+        // Sin escapado automático los filtros se aplican a mano
+        let plain = TemplateRenderer::new()
+            .render("{{ title | escape_html }}", &context)
+            .unwrap();
+        assert_eq!(plain, "&lt;Rust&gt; &amp; *Go_lang* v1.2!");
+    }
+
+    #[test]
+    fn test_to_hashtag() {
+        assert_eq!(to_hashtag("rust-lang").as_deref(), Some("#RustLang"));
+        assert_eq!(
+            to_hashtag("Programación web").as_deref(),
+            Some("#ProgramacionWeb")
+        );
+        assert_eq!(to_hashtag("#GitHub").as_deref(), Some("#GitHub"));
+        assert_eq!(to_hashtag("2024"), None);
+        assert_eq!(to_hashtag(" - "), None);
+    }
+
+    #[test]
+    fn test_hashtags_filter_and_mapping() {
+        let hashtag_map = HashMap::from([
+            ("rust-lang".to_string(), "#RustLang".to_string()),
+            ("General".to_string(), String::new()),
+        ]);
+        let hashtags = build_hashtags(
+            &[
+                "Rust-Lang".to_string(),
+                "general".to_string(),
+                "Linux".to_string(),
+            ],
+            &hashtag_map,
+            &["Podcast".to_string(), "linux".to_string()],
+        );
+        assert_eq!(hashtags, vec!["#RustLang", "#Linux", "#Podcast"]);
+
+        let renderer = TemplateRenderer::new();
+        let context = TemplateContext {
+            categories: vec!["open source".to_string(), "café".to_string()],
+            hashtags,
+            ..Default::default()
+        };
+        let result = renderer
+            .render(
+                "{{ categories | hashtags }}|{{ hashtags | hashtags(2) }}",
+                &context,
+            )
+            .unwrap();
+        assert_eq!(result, "#OpenSource #Cafe|#RustLang #Linux");
+    }
 }
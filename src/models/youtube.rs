@@ -53,10 +53,8 @@ pub struct VideoSnippet {
     pub published_at: String,
     pub title: String,
     pub description: String,
-    #[allow(dead_code)]
     pub thumbnails: HashMap<String, Thumbnail>,
     #[serde(rename = "channelTitle")]
-    #[allow(dead_code)]
     pub channel_title: String,
     #[serde(rename = "resourceId")]
     pub resource_id: Option<ResourceId>,
@@ -70,7 +68,6 @@ pub struct ResourceId {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Thumbnail {
-    #[allow(dead_code)]
     pub url: String,
     #[allow(dead_code)]
     pub width: Option<u32>,
@@ -239,7 +236,7 @@ impl YouTubeClient {
             let pub_date =
                 DateTime::parse_from_rfc3339(&video.snippet.published_at)?.with_timezone(&Utc);
 
-            let post = Post::new(
+            let mut post = Post::new(
                 video_id.to_string(),
                 video.snippet.title.clone(),
                 Some(video.snippet.description.clone()),
@@ -247,6 +244,9 @@ impl YouTubeClient {
                 pub_date,
                 "youtube".to_string(),
             );
+            post.image_url = best_thumbnail(&video.snippet.thumbnails);
+            post.author = Some(video.snippet.channel_title.clone()).filter(|a| !a.is_empty());
+            post.content = Some(video.snippet.description.clone()).filter(|d| !d.is_empty());
 
             log::info!("Adding video: '{}'", video.snippet.title);
             posts.push(post);
@@ -264,3 +264,11 @@ impl YouTubeClient {
         Ok(posts)
     }
 }
+
+/// Pick the largest thumbnail the API returned
+fn best_thumbnail(thumbnails: &HashMap<String, Thumbnail>) -> Option<String> {
+    ["maxres", "standard", "high", "medium", "default"]
+        .iter()
+        .find_map(|size| thumbnails.get(*size))
+        .map(|thumbnail| thumbnail.url.clone())
+}
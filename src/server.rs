@@ -0,0 +1,117 @@
+use crate::metrics;
+use anyhow::Result;
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// Startup progress and liveness settings shared with the health endpoints
+pub struct HealthState {
+    config_loaded: AtomicBool,
+    storage_ready: AtomicBool,
+    publishers_ready: AtomicUsize,
+    started_at: i64,
+    max_cycle_age_seconds: i64,
+}
+
+impl HealthState {
+    pub fn new(default_interval_minutes: u64, interval_multiplier: u64) -> Self {
+        Self {
+            config_loaded: AtomicBool::new(false),
+            storage_ready: AtomicBool::new(false),
+            publishers_ready: AtomicUsize::new(0),
+            started_at: chrono::Utc::now().timestamp(),
+            max_cycle_age_seconds: (default_interval_minutes * interval_multiplier * 60) as i64,
+        }
+    }
+
+    pub fn set_config_loaded(&self) {
+        self.config_loaded.store(true, Ordering::Relaxed);
+    }
+
+    pub fn set_storage_ready(&self) {
+        self.storage_ready.store(true, Ordering::Relaxed);
+    }
+
+    pub fn set_publishers_ready(&self, count: usize) {
+        self.publishers_ready.store(count, Ordering::Relaxed);
+    }
+
+    /// Reasons the service is not ready yet, empty when ready
+    fn readiness_problems(&self) -> Vec<&'static str> {
+        let mut problems = Vec::new();
+        if !self.config_loaded.load(Ordering::Relaxed) {
+            problems.push("config not loaded");
+        }
+        if !self.storage_ready.load(Ordering::Relaxed) {
+            problems.push("storage not initialized");
+        }
+        if self.publishers_ready.load(Ordering::Relaxed) == 0 {
+            problems.push("no publisher initialized");
+        }
+        problems
+    }
+
+    /// Healthy while feed check cycles keep finishing. Before the first cycle,
+    /// the time since startup is used instead.
+    fn health_problem(&self) -> Option<String> {
+        let now = chrono::Utc::now().timestamp();
+        let last_activity = metrics::last_successful_cycle().unwrap_or(self.started_at);
+        let age = now - last_activity;
+
+        (age > self.max_cycle_age_seconds).then(|| {
+            format!(
+                "no feed check cycle finished in the last {}s (limit {}s)",
+                age, self.max_cycle_age_seconds
+            )
+        })
+    }
+}
+
+/// Bind the HTTP listener up front so a bad address fails at startup
+pub async fn bind(listen_address: &str) -> Result<TcpListener> {
+    let listener = TcpListener::bind(listen_address).await.map_err(|e| {
+        anyhow::anyhow!("Failed to bind HTTP listener on {}: {}", listen_address, e)
+    })?;
+    log::info!("HTTP server listening on {}", listen_address);
+    Ok(listener)
+}
+
+pub async fn serve(listener: TcpListener, health: Arc<HealthState>) {
+    let app = Router::new()
+        .route("/metrics", get(metrics_handler))
+        .route("/healthz", get(health_handler))
+        .route("/readyz", get(ready_handler))
+        .with_state(health);
+
+    if let Err(e) = axum::serve(listener, app).await {
+        log::error!("HTTP server stopped: {}", e);
+    }
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render(),
+    )
+}
+
+async fn health_handler(State(health): State<Arc<HealthState>>) -> impl IntoResponse {
+    match health.health_problem() {
+        None => (StatusCode::OK, "ok".to_string()),
+        Some(problem) => (StatusCode::SERVICE_UNAVAILABLE, problem),
+    }
+}
+
+async fn ready_handler(State(health): State<Arc<HealthState>>) -> impl IntoResponse {
+    let problems = health.readiness_problems();
+    if problems.is_empty() {
+        (StatusCode::OK, "ready".to_string())
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, problems.join(", "))
+    }
+}
@@ -0,0 +1,723 @@
+mod sqlite;
+
+use crate::models::{
+    AppConfig, FeedCacheMetadata, PublishedPostsStorage, RetryQueue, StorageBackend, StorageConfig,
+};
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sqlite::SqliteStore;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const LOCK_FILE: &str = "populatrs.lock";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FeedCacheStorage {
+    pub feeds: HashMap<String, FeedCacheMetadata>,
+}
+
+/// Advisory lock on the data directory, released when dropped
+pub struct StorageLock {
+    _file: fs::File,
+}
+
+#[derive(Clone)]
+pub struct StorageManager {
+    data_dir: String,
+    published_posts_file: String,
+    feed_cache_file: String,
+    retry_queue_file: String,
+    sqlite: Option<SqliteStore>,
+}
+
+impl StorageManager {
+    pub fn new(config: &StorageConfig) -> Self {
+        let sqlite = match config.backend {
+            StorageBackend::Json => None,
+            StorageBackend::Sqlite => Some(SqliteStore::new(
+                Path::new(&config.data_dir).join(&config.database_file),
+            )),
+        };
+
+        Self {
+            data_dir: config.data_dir.clone(),
+            published_posts_file: config.published_posts_file.clone(),
+            feed_cache_file: "feed_cache.json".to_string(),
+            retry_queue_file: "retry_queue.json".to_string(),
+            sqlite,
+        }
+    }
+
+    /// Create the data directory and lock it for this process. The returned
+    /// lock must be kept alive for as long as the storage is in use.
+    pub fn init(&self) -> Result<StorageLock> {
+        // Create data directory if it doesn't exist
+        fs::create_dir_all(&self.data_dir)?;
+        let lock = self.lock()?;
+
+        if let Some(sqlite) = &self.sqlite {
+            sqlite.init()?;
+            self.migrate_json_to_sqlite(sqlite)?;
+            self.migrate_retry_queue_to_sqlite(sqlite)?;
+        }
+
+        log::info!("Storage initialized in directory: {}", self.data_dir);
+        Ok(lock)
+    }
+
+    fn lock(&self) -> Result<StorageLock> {
+        let lock_path = Path::new(&self.data_dir).join(LOCK_FILE);
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("Failed to open lock file {}", lock_path.display()))?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(fs::TryLockError::WouldBlock) => {
+                return Err(anyhow::anyhow!(
+                    "Data directory {} is already in use by another populatrs instance (lock file: {})",
+                    self.data_dir,
+                    lock_path.display()
+                ));
+            }
+            Err(fs::TryLockError::Error(e)) => {
+                return Err(anyhow::anyhow!(
+                    "Failed to lock data directory {}: {}",
+                    self.data_dir,
+                    e
+                ));
+            }
+        }
+
+        // Record the owner to make a held lock easier to diagnose
+        file.set_len(0)?;
+        writeln!(file, "{}", std::process::id())?;
+
+        Ok(StorageLock { _file: file })
+    }
+
+    /// One-time import of the JSON state files into a fresh SQLite database.
+    /// The imported files are renamed with a `.migrated` suffix.
+    fn migrate_json_to_sqlite(&self, sqlite: &SqliteStore) -> Result<()> {
+        if sqlite.is_json_migrated()? {
+            return Ok(());
+        }
+
+        let storage = self.load_published_posts_json()?;
+        let cache = self.load_feed_cache_json()?;
+        sqlite.import_json(&storage, &cache)?;
+
+        log::info!(
+            "Migrated {} published posts and cache for {} feeds from JSON to SQLite",
+            storage.posts.len(),
+            cache.feeds.len()
+        );
+
+        for file_name in [&self.published_posts_file, &self.feed_cache_file] {
+            let path = Path::new(&self.data_dir).join(file_name);
+            if path.exists() {
+                let migrated = Path::new(&self.data_dir).join(format!("{}.migrated", file_name));
+                fs::rename(&path, &migrated)?;
+                log::info!("Renamed {} to {}", path.display(), migrated.display());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Move a `retry_queue.json` left from the JSON backend, or from a version
+    /// that kept the queue in JSON next to SQLite, into the database
+    fn migrate_retry_queue_to_sqlite(&self, sqlite: &SqliteStore) -> Result<()> {
+        let Some(json_queue) = self.load_json_file::<RetryQueue>(&self.retry_queue_file)? else {
+            return Ok(());
+        };
+
+        let imported = json_queue.entries.len();
+        let mut queue = sqlite.load_retry_queue()?;
+        queue.entries.extend(json_queue.entries);
+        sqlite.save_retry_queue(&queue)?;
+
+        let path = Path::new(&self.data_dir).join(&self.retry_queue_file);
+        let migrated =
+            Path::new(&self.data_dir).join(format!("{}.migrated", self.retry_queue_file));
+        fs::rename(&path, &migrated)?;
+        log::info!(
+            "Migrated {} pending retries from {} to SQLite",
+            imported,
+            path.display()
+        );
+        Ok(())
+    }
+
+    pub fn load_published_posts(&self) -> Result<PublishedPostsStorage> {
+        let storage = match &self.sqlite {
+            Some(sqlite) => sqlite.load_published_posts()?,
+            None => self.load_published_posts_json()?,
+        };
+
+        log::info!(
+            "Loaded {} published posts from storage",
+            storage.posts.len()
+        );
+        Ok(storage)
+    }
+
+    /// Persist published posts and clear the pending changes on success
+    pub fn save_published_posts(&self, storage: &mut PublishedPostsStorage) -> Result<()> {
+        match &self.sqlite {
+            Some(sqlite) => sqlite.save_published_posts(storage)?,
+            None => self.save_published_posts_json(storage)?,
+        }
+        storage.clear_pending_changes();
+        log::debug!("Saved {} published posts to storage", storage.posts.len());
+        Ok(())
+    }
+
+    pub fn load_feed_cache(&self) -> Result<FeedCacheStorage> {
+        let cache = match &self.sqlite {
+            Some(sqlite) => sqlite.load_feed_cache()?,
+            None => self.load_feed_cache_json()?,
+        };
+
+        log::info!("Loaded cache for {} feeds from storage", cache.feeds.len());
+        Ok(cache)
+    }
+
+    pub fn save_feed_cache(&self, cache: &FeedCacheStorage) -> Result<()> {
+        match &self.sqlite {
+            Some(sqlite) => sqlite.save_feed_cache(cache)?,
+            None => self.save_feed_cache_json(cache)?,
+        }
+        log::debug!("Saved cache for {} feeds to storage", cache.feeds.len());
+        Ok(())
+    }
+
+    fn load_published_posts_json(&self) -> Result<PublishedPostsStorage> {
+        match self.load_json_file::<PublishedPostsStorage>(&self.published_posts_file)? {
+            Some(mut storage) => {
+                storage.rebuild_index();
+                Ok(storage)
+            }
+            None => {
+                log::info!("Published posts file doesn't exist, creating new storage");
+                Ok(PublishedPostsStorage::new())
+            }
+        }
+    }
+
+    fn save_published_posts_json(&self, storage: &PublishedPostsStorage) -> Result<()> {
+        let file_path = Path::new(&self.data_dir).join(&self.published_posts_file);
+        let content = serde_json::to_string_pretty(storage)?;
+        write_atomic(&file_path, &content)
+    }
+
+    fn load_feed_cache_json(&self) -> Result<FeedCacheStorage> {
+        match self.load_json_file(&self.feed_cache_file)? {
+            Some(cache) => Ok(cache),
+            None => {
+                log::info!("Feed cache file doesn't exist, creating new cache storage");
+                Ok(FeedCacheStorage::default())
+            }
+        }
+    }
+
+    fn save_feed_cache_json(&self, cache: &FeedCacheStorage) -> Result<()> {
+        let file_path = Path::new(&self.data_dir).join(&self.feed_cache_file);
+        let content = serde_json::to_string_pretty(cache)?;
+        write_atomic(&file_path, &content)
+    }
+
+    pub fn load_retry_queue(&self) -> Result<RetryQueue> {
+        let queue = match &self.sqlite {
+            Some(sqlite) => sqlite.load_retry_queue()?,
+            None => match self.load_json_file::<RetryQueue>(&self.retry_queue_file)? {
+                Some(queue) => queue,
+                None => {
+                    log::info!("Retry queue file doesn't exist, creating empty queue");
+                    return Ok(RetryQueue::new());
+                }
+            },
+        };
+
+        log::info!("Loaded {} pending retries from storage", queue.len());
+        Ok(queue)
+    }
+
+    pub fn save_retry_queue(&self, queue: &RetryQueue) -> Result<()> {
+        match &self.sqlite {
+            Some(sqlite) => sqlite.save_retry_queue(queue)?,
+            None => {
+                let file_path = Path::new(&self.data_dir).join(&self.retry_queue_file);
+                let content = serde_json::to_string_pretty(queue)?;
+                write_atomic(&file_path, &content)?;
+            }
+        }
+        log::debug!("Saved {} pending retries to storage", queue.len());
+        Ok(())
+    }
+
+    /// Load a JSON state file from the data directory, or None if it doesn't exist.
+    /// A file that fails to parse is restored from its newest valid backup; if
+    /// there is none this is an error, never a silent reset to empty state.
+    fn load_json_file<T: DeserializeOwned>(&self, file_name: &str) -> Result<Option<T>> {
+        let file_path = Path::new(&self.data_dir).join(file_name);
+
+        if !file_path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&file_path)
+            .with_context(|| format!("Failed to read {}", file_path.display()))?;
+        let parse_error = match serde_json::from_str(&content) {
+            Ok(value) => return Ok(Some(value)),
+            Err(e) => e,
+        };
+
+        log::error!("Failed to parse {}: {}", file_path.display(), parse_error);
+
+        for backup_path in self.list_backups(file_name)? {
+            let backup_content = match fs::read_to_string(&backup_path) {
+                Ok(content) => content,
+                Err(e) => {
+                    log::warn!("Failed to read backup {}: {}", backup_path.display(), e);
+                    continue;
+                }
+            };
+
+            match serde_json::from_str(&backup_content) {
+                Ok(value) => {
+                    // Keep the corrupt file around for inspection before restoring
+                    let corrupt_path = Path::new(&self.data_dir).join(format!(
+                        "{}.corrupt.{}",
+                        file_name,
+                        chrono::Utc::now().format("%Y%m%d_%H%M%S")
+                    ));
+                    fs::rename(&file_path, &corrupt_path)?;
+                    write_atomic(&file_path, &backup_content)?;
+                    log::warn!(
+                        "Restored {} from backup {} (corrupt file moved to {})",
+                        file_path.display(),
+                        backup_path.display(),
+                        corrupt_path.display()
+                    );
+                    return Ok(Some(value));
+                }
+                Err(e) => {
+                    log::warn!("Skipping invalid backup {}: {}", backup_path.display(), e);
+                }
+            }
+        }
+
+        Err(anyhow::anyhow!(
+            "{} is corrupt ({}) and no valid backup was found. Fix or restore the file manually before starting again",
+            file_path.display(),
+            parse_error
+        ))
+    }
+
+    /// Backups of a state file, newest first
+    fn list_backups(&self, file_name: &str) -> Result<Vec<PathBuf>> {
+        let prefix = format!("{}.backup.", file_name);
+        let mut backups = Vec::new();
+
+        for entry in fs::read_dir(&self.data_dir)? {
+            let path = entry?.path();
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                if name.starts_with(&prefix) {
+                    backups.push(path);
+                }
+            }
+        }
+
+        // Backup suffixes are %Y%m%d_%H%M%S timestamps, so names sort chronologically
+        backups.sort();
+        backups.reverse();
+        Ok(backups)
+    }
+
+    pub fn load_config_from_file(file_path: &str) -> Result<AppConfig> {
+        if !Path::new(file_path).exists() {
+            log::warn!(
+                "Config file {} doesn't exist, creating default config",
+                file_path
+            );
+            let default_config = AppConfig::default();
+            let content = serde_json::to_string_pretty(&default_config)?;
+            write_atomic(Path::new(file_path), &content)?;
+            return Ok(default_config);
+        }
+
+        let content = fs::read_to_string(file_path)?;
+        let config: AppConfig = serde_json::from_str(&content)?;
+        log::info!("Loaded configuration from: {}", file_path);
+        Ok(config)
+    }
+
+    pub fn save_config_to_file(config: &AppConfig, file_path: &str) -> Result<()> {
+        let content = serde_json::to_string_pretty(config)?;
+        write_atomic(Path::new(file_path), &content)?;
+        log::info!("Saved configuration to: {}", file_path);
+        Ok(())
+    }
+
+    /// Back up every JSON state file that exists in the data directory
+    pub fn backup_state_files(&self) -> Result<()> {
+        for file_name in [
+            &self.published_posts_file,
+            &self.feed_cache_file,
+            &self.retry_queue_file,
+        ] {
+            self.backup_file(file_name)?;
+        }
+        Ok(())
+    }
+
+    fn backup_file(&self, file_name: &str) -> Result<()> {
+        let source_path = Path::new(&self.data_dir).join(file_name);
+        if !source_path.exists() {
+            return Ok(());
+        }
+
+        let backup_filename = format!(
+            "{}.backup.{}",
+            file_name,
+            chrono::Utc::now().format("%Y%m%d_%H%M%S")
+        );
+        let backup_path = Path::new(&self.data_dir).join(backup_filename);
+
+        let content = fs::read_to_string(&source_path)?;
+        write_atomic(&backup_path, &content)?;
+        log::info!("Created backup: {}", backup_path.display());
+        Ok(())
+    }
+
+    pub fn cleanup_old_backups(&self, days_to_keep: u64) -> Result<()> {
+        let dir = fs::read_dir(&self.data_dir)?;
+        let cutoff_date = std::time::SystemTime::now()
+            - std::time::Duration::from_secs(days_to_keep * 24 * 60 * 60);
+
+        for entry in dir {
+            let entry = entry?;
+            let path = entry.path();
+
+            if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
+                if filename.contains(".backup.") {
+                    if let Ok(metadata) = entry.metadata() {
+                        if let Ok(created) = metadata.created() {
+                            if created < cutoff_date {
+                                if let Err(e) = fs::remove_file(&path) {
+                                    log::warn!(
+                                        "Failed to remove old backup {}: {}",
+                                        path.display(),
+                                        e
+                                    );
+                                } else {
+                                    log::info!("Removed old backup: {}", path.display());
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Write `content` to a temporary file next to `path`, flush it to disk and
+/// rename it over `path`, so a crash never leaves a truncated file behind
+pub fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    write_atomic_with_mode(path, content, false)
+}
+
+/// Like [`write_atomic`], but on Unix the file is only readable and writable
+/// by its owner (mode 0600). For files holding credentials.
+pub fn write_atomic_private(path: &Path, content: &str) -> Result<()> {
+    write_atomic_with_mode(path, content, true)
+}
+
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn write_atomic_with_mode(path: &Path, content: &str, private: bool) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Invalid file path: {}", path.display()))?;
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+    let result = (|| -> Result<()> {
+        let mut file = fs::File::create(&tmp_path)?;
+        // Restricted before anything is written, also for a leftover temporary file
+        #[cfg(unix)]
+        if private {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e.context(format!("Failed to write {}", path.display())));
+    }
+
+    // Flush the directory entry so the rename itself survives a crash
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    if let Ok(dir) = fs::File::open(dir) {
+        let _ = dir.sync_all();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Post;
+    use chrono::{Duration, Utc};
+
+    fn manager(dir: &Path, backend: StorageBackend) -> StorageManager {
+        StorageManager::new(&StorageConfig {
+            data_dir: dir.to_string_lossy().into_owned(),
+            published_posts_file: "published_posts.json".to_string(),
+            backend,
+            database_file: "populatrs.db".to_string(),
+        })
+    }
+
+    fn published(storage: &mut PublishedPostsStorage, guid: &str) {
+        let post = Post::for_test("feed", guid, Utc::now());
+        storage.mark_published(&post, vec![("x".to_string(), true, "ok".to_string())]);
+    }
+
+    fn cache(feed_ids: &[&str]) -> FeedCacheStorage {
+        let mut cache = FeedCacheStorage::default();
+        for feed_id in feed_ids {
+            cache.feeds.insert(
+                feed_id.to_string(),
+                FeedCacheMetadata {
+                    etag: Some(format!("\"{}\"", feed_id)),
+                    last_post_date: Some(Utc::now()),
+                    ..Default::default()
+                },
+            );
+        }
+        cache
+    }
+
+    #[test]
+    fn test_sqlite_published_posts_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage_manager = manager(dir.path(), StorageBackend::Sqlite);
+        let _lock = storage_manager.init().unwrap();
+
+        let mut storage = storage_manager.load_published_posts().unwrap();
+        published(&mut storage, "old");
+        published(&mut storage, "new");
+        storage_manager.save_published_posts(&mut storage).unwrap();
+
+        let mut storage = storage_manager.load_published_posts().unwrap();
+        assert_eq!(storage.posts.len(), 2);
+        assert!(storage.is_published(&Post::for_test("feed", "old", Utc::now())));
+
+        // Only the expired post is deleted by the incremental save
+        let old = storage.posts.iter_mut().find(|p| p.post_guid == "old");
+        old.unwrap().published_at = Utc::now() - Duration::days(40);
+        storage.cleanup_old_posts(30);
+        storage_manager.save_published_posts(&mut storage).unwrap();
+
+        let storage = storage_manager.load_published_posts().unwrap();
+        assert_eq!(storage.posts.len(), 1);
+        assert_eq!(storage.posts[0].post_guid, "new");
+        assert_eq!(storage.posts[0].publisher_results.len(), 1);
+    }
+
+    #[test]
+    fn test_sqlite_feed_cache_forgets_removed_feeds() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage_manager = manager(dir.path(), StorageBackend::Sqlite);
+        let _lock = storage_manager.init().unwrap();
+
+        storage_manager
+            .save_feed_cache(&cache(&["a", "b"]))
+            .unwrap();
+        storage_manager.save_feed_cache(&cache(&["b"])).unwrap();
+
+        let loaded = storage_manager.load_feed_cache().unwrap();
+        assert_eq!(loaded.feeds.len(), 1);
+        assert_eq!(loaded.feeds["b"].etag.as_deref(), Some("\"b\""));
+        assert!(loaded.feeds["b"].last_post_date.is_some());
+    }
+
+    #[test]
+    fn test_json_to_sqlite_migration() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let json = manager(dir.path(), StorageBackend::Json);
+            let _lock = json.init().unwrap();
+            let mut storage = PublishedPostsStorage::new();
+            published(&mut storage, "a");
+            json.save_published_posts(&mut storage).unwrap();
+            json.save_feed_cache(&cache(&["feed"])).unwrap();
+        }
+
+        let sqlite = manager(dir.path(), StorageBackend::Sqlite);
+        {
+            let _lock = sqlite.init().unwrap();
+            assert_eq!(sqlite.load_published_posts().unwrap().posts.len(), 1);
+            assert_eq!(sqlite.load_feed_cache().unwrap().feeds.len(), 1);
+        }
+        assert!(!dir.path().join("published_posts.json").exists());
+        assert!(dir.path().join("published_posts.json.migrated").exists());
+        assert!(dir.path().join("feed_cache.json.migrated").exists());
+
+        // Migrated once: JSON files that show up later are not imported
+        sqlite.save_feed_cache(&cache(&["other"])).unwrap();
+        manager(dir.path(), StorageBackend::Json)
+            .save_feed_cache(&cache(&["stale"]))
+            .unwrap();
+        let _lock = sqlite.init().unwrap();
+        let loaded = sqlite.load_feed_cache().unwrap();
+        assert_eq!(loaded.feeds.keys().collect::<Vec<_>>(), vec!["other"]);
+    }
+
+    #[test]
+    fn test_sqlite_retry_queue() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = crate::models::RetryConfig::default();
+        let queued = |guids: &[&str]| {
+            let mut queue = RetryQueue::new();
+            for guid in guids {
+                let post = Post::for_test("feed", guid, Utc::now());
+                queue.enqueue(&post, "x", "timeout".to_string(), &config);
+            }
+            queue
+        };
+        let guids = |queue: RetryQueue| {
+            queue
+                .entries
+                .into_iter()
+                .map(|entry| entry.post.guid)
+                .collect::<Vec<_>>()
+        };
+
+        // A queue left by the JSON backend is moved into the database
+        manager(dir.path(), StorageBackend::Json)
+            .save_retry_queue(&queued(&["a"]))
+            .unwrap();
+        let sqlite = manager(dir.path(), StorageBackend::Sqlite);
+        let _lock = sqlite.init().unwrap();
+        assert!(!dir.path().join("retry_queue.json").exists());
+        assert!(dir.path().join("retry_queue.json.migrated").exists());
+        assert_eq!(guids(sqlite.load_retry_queue().unwrap()), vec!["a"]);
+
+        sqlite.save_retry_queue(&queued(&["b", "c"])).unwrap();
+        assert!(!dir.path().join("retry_queue.json").exists());
+        assert_eq!(guids(sqlite.load_retry_queue().unwrap()), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_corrupt_file_restored_from_newest_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage_manager = manager(dir.path(), StorageBackend::Json);
+
+        let write = |name: &str, content: &str| fs::write(dir.path().join(name), content).unwrap();
+        write(
+            "feed_cache.json.backup.20240101_000000",
+            r#"{"feeds":{"old":{}}}"#,
+        );
+        write(
+            "feed_cache.json.backup.20240102_000000",
+            r#"{"feeds":{"new":{}}}"#,
+        );
+        write("feed_cache.json.backup.20240103_000000", "{ truncated");
+        write("feed_cache.json", "{ truncated");
+
+        let loaded = storage_manager.load_feed_cache().unwrap();
+        assert_eq!(loaded.feeds.keys().collect::<Vec<_>>(), vec!["new"]);
+
+        // The restored copy replaces the corrupt file, which is kept aside
+        let restored = fs::read_to_string(dir.path().join("feed_cache.json")).unwrap();
+        assert_eq!(restored, r#"{"feeds":{"new":{}}}"#);
+        let corrupt = fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|entry| entry.unwrap().file_name().into_string().ok())
+            .filter(|name| name.starts_with("feed_cache.json.corrupt."))
+            .count();
+        assert_eq!(corrupt, 1);
+    }
+
+    #[test]
+    fn test_corrupt_file_without_backup_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage_manager = manager(dir.path(), StorageBackend::Json);
+        fs::write(dir.path().join("published_posts.json"), "[").unwrap();
+
+        let error = storage_manager.load_published_posts().unwrap_err();
+        assert!(error.to_string().contains("no valid backup"));
+        // Nothing is reset or moved
+        let content = fs::read_to_string(dir.path().join("published_posts.json")).unwrap();
+        assert_eq!(content, "[");
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        let names = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["state.json"]);
+
+        // A write that fails leaves the previous content in place
+        fs::create_dir(dir.path().join(".state.json.tmp")).unwrap();
+        assert!(write_atomic(&path, "third").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_private_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        write_atomic(&path, "public").unwrap();
+        write_atomic_private(&path, "secret").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "secret");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_second_lock_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage_manager = manager(dir.path(), StorageBackend::Json);
+
+        let lock = storage_manager.init().unwrap();
+        let error = storage_manager.init().err().unwrap();
+        assert!(error.to_string().contains("already in use"));
+
+        drop(lock);
+        assert!(storage_manager.init().is_ok());
+    }
+}
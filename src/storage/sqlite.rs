@@ -0,0 +1,223 @@
+use super::FeedCacheStorage;
+use crate::models::{PublishedPost, PublishedPostsStorage, RetryQueue};
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS published_posts (
+        feed_id TEXT NOT NULL,
+        post_guid TEXT NOT NULL,
+        published_at TEXT NOT NULL,
+        publisher_results TEXT NOT NULL,
+        PRIMARY KEY (feed_id, post_guid)
+    );
+    CREATE INDEX IF NOT EXISTS idx_published_posts_published_at
+        ON published_posts (published_at);
+    CREATE TABLE IF NOT EXISTS feed_cache (
+        feed_id TEXT PRIMARY KEY,
+        metadata TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS retry_queue (
+        position INTEGER PRIMARY KEY,
+        entry TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS settings (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+";
+
+const JSON_MIGRATED_KEY: &str = "json_migrated_at";
+
+/// SQLite backed storage for published posts, feed cache metadata and the
+/// retry queue
+#[derive(Clone)]
+pub struct SqliteStore {
+    path: PathBuf,
+}
+
+impl SqliteStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn connect(&self) -> Result<Connection> {
+        let conn = Connection::open(&self.path)
+            .with_context(|| format!("Failed to open database {}", self.path.display()))?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        Ok(conn)
+    }
+
+    pub fn init(&self) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute_batch(SCHEMA)?;
+        log::info!("SQLite storage initialized: {}", self.path.display());
+        Ok(())
+    }
+
+    pub fn is_json_migrated(&self) -> Result<bool> {
+        let conn = self.connect()?;
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                params![JSON_MIGRATED_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value.is_some())
+    }
+
+    /// Import JSON storage in a single transaction and flag the migration as done
+    pub fn import_json(
+        &self,
+        storage: &PublishedPostsStorage,
+        cache: &FeedCacheStorage,
+    ) -> Result<()> {
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+        for post in &storage.posts {
+            Self::upsert_post(&tx, post)?;
+        }
+        for (feed_id, metadata) in &cache.feeds {
+            Self::upsert_feed_cache(&tx, feed_id, &serde_json::to_string(metadata)?)?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            params![JSON_MIGRATED_KEY, chrono::Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_published_posts(&self) -> Result<PublishedPostsStorage> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare(
+            "SELECT feed_id, post_guid, published_at, publisher_results FROM published_posts",
+        )?;
+
+        let posts = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })?
+            .map(|row| {
+                let (feed_id, post_guid, published_at, results) = row?;
+                Ok(PublishedPost {
+                    post_guid,
+                    feed_id,
+                    published_at,
+                    publisher_results: serde_json::from_str(&results)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(PublishedPostsStorage::from_posts(posts))
+    }
+
+    /// Write only the posts added, updated or removed since the last save
+    pub fn save_published_posts(&self, storage: &PublishedPostsStorage) -> Result<()> {
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+        for post in storage.changed_posts() {
+            Self::upsert_post(&tx, post)?;
+        }
+        for (feed_id, post_guid) in storage.removed_keys() {
+            tx.execute(
+                "DELETE FROM published_posts WHERE feed_id = ?1 AND post_guid = ?2",
+                params![feed_id, post_guid],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_feed_cache(&self) -> Result<FeedCacheStorage> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare("SELECT feed_id, metadata FROM feed_cache")?;
+
+        let feeds = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .map(|row| {
+                let (feed_id, metadata) = row?;
+                Ok((feed_id, serde_json::from_str(&metadata)?))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(FeedCacheStorage { feeds })
+    }
+
+    /// Replace the cache; like the JSON file, the table then holds exactly the
+    /// feeds in `cache`, so feeds removed from the config are forgotten
+    pub fn save_feed_cache(&self, cache: &FeedCacheStorage) -> Result<()> {
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM feed_cache", [])?;
+        for (feed_id, metadata) in &cache.feeds {
+            Self::upsert_feed_cache(&tx, feed_id, &serde_json::to_string(metadata)?)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_retry_queue(&self) -> Result<RetryQueue> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare("SELECT entry FROM retry_queue ORDER BY position")?;
+
+        let entries = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|entry| Ok(serde_json::from_str(&entry?)?))
+            .collect::<Result<_>>()?;
+
+        Ok(RetryQueue { entries })
+    }
+
+    /// Replace the queue; it is small, so it is written out whole
+    pub fn save_retry_queue(&self, queue: &RetryQueue) -> Result<()> {
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM retry_queue", [])?;
+        for (position, entry) in queue.entries.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO retry_queue (position, entry) VALUES (?1, ?2)",
+                params![position as i64, serde_json::to_string(entry)?],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn upsert_post(conn: &Connection, post: &PublishedPost) -> Result<()> {
+        conn.execute(
+            "INSERT INTO published_posts (feed_id, post_guid, published_at, publisher_results)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (feed_id, post_guid) DO UPDATE SET
+                published_at = excluded.published_at,
+                publisher_results = excluded.publisher_results",
+            params![
+                post.feed_id,
+                post.post_guid,
+                post.published_at,
+                serde_json::to_string(&post.publisher_results)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn upsert_feed_cache(conn: &Connection, feed_id: &str, metadata: &str) -> Result<()> {
+        conn.execute(
+            "INSERT INTO feed_cache (feed_id, metadata) VALUES (?1, ?2)
+             ON CONFLICT (feed_id) DO UPDATE SET metadata = excluded.metadata",
+            params![feed_id, metadata],
+        )?;
+        Ok(())
+    }
+}